@@ -0,0 +1,322 @@
+//! How closely the review log has kept to the schedule: streaks, the share of
+//! scheduled reviews that were done, and verses that have gone unreviewed.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use chrono::{Days, NaiveDate};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::review::{Outcome, ReviewLog};
+use crate::{Frequency, Reference, ScheduleConfig, Scheduler, VersesForADay};
+
+/// How many of the reviews scheduled in a phase or month were done.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Completion {
+    pub scheduled: usize,
+    pub completed: usize,
+}
+
+impl Completion {
+    /// Completed reviews as a percentage of scheduled ones, or `None` if
+    /// nothing was scheduled.
+    pub fn percent(&self) -> Option<f64> {
+        (self.scheduled > 0).then(|| 100.0 * self.completed as f64 / self.scheduled as f64)
+    }
+}
+
+impl Serialize for Completion {
+    /// Includes the percentage, e.g.
+    /// `{"scheduled":40,"completed":30,"percent":75.0}`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Completion", 3)?;
+        state.serialize_field("scheduled", &self.scheduled)?;
+        state.serialize_field("completed", &self.completed)?;
+        state.serialize_field("percent", &self.percent())?;
+        state.end()
+    }
+}
+
+impl fmt::Display for Completion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.completed, self.scheduled)?;
+        if let Some(percent) = self.percent() {
+            write!(f, " ({percent:.1}%)")?;
+        }
+        Ok(())
+    }
+}
+
+/// A verse that was scheduled since its last review but not reviewed.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Overdue {
+    pub reference: Reference,
+    pub phase: Frequency,
+    /// `None` if it hasn't been reviewed since the log was started.
+    pub last_reviewed: Option<NaiveDate>,
+    /// Days since it was last reviewed, or since the log or the verse started.
+    pub days: i64,
+}
+
+/// Streaks and completion rates from a review log, as of a date.
+///
+/// Only days from the first one in the log are counted, so a log started
+/// partway through a plan isn't penalized for the days before it.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Adherence {
+    /// Days in a row with at least one verse reviewed, up to the date or the
+    /// day before it.
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    /// Reviews done out of those scheduled before the date, by phase.
+    pub phases: BTreeMap<Frequency, Completion>,
+    /// Reviews done out of those scheduled before the date, by the first day
+    /// of their month.
+    pub months: BTreeMap<NaiveDate, Completion>,
+    pub overdue: Vec<Overdue>,
+}
+
+impl Adherence {
+    /// Compares `log` to the days before `scheduler`'s date, as scheduled by
+    /// `for_day`, and checks the verses `scheduler` is reviewing for ones
+    /// that `for_day` scheduled again after their last review. Months are
+    /// counted as `config` counts them from `anchor`.
+    pub fn new<'a>(
+        log: &ReviewLog,
+        scheduler: &(impl Scheduler<'a> + ?Sized),
+        config: &ScheduleConfig,
+        anchor: NaiveDate,
+        for_day: impl Fn(NaiveDate) -> VersesForADay<'a>,
+    ) -> Self {
+        let date = scheduler.date();
+        let Some(first) = log.reviews().iter().map(|review| review.date()).min() else {
+            return Self::default();
+        };
+        let (current_streak, longest_streak) = streaks(log, date);
+
+        let done: HashSet<(NaiveDate, Reference)> = log
+            .reviews()
+            .iter()
+            .filter(|review| review.outcome() == Outcome::Reviewed)
+            .map(|review| (review.date(), *review.reference()))
+            .collect();
+        let mut phases = BTreeMap::<Frequency, Completion>::new();
+        let mut months = BTreeMap::<NaiveDate, Completion>::new();
+        let mut last_scheduled = HashMap::<Reference, NaiveDate>::new();
+        for day in first.iter_days().take_while(|day| *day < date) {
+            let month = config.start_of_month(anchor, day);
+            for (phase, verse) in for_day(day).items() {
+                last_scheduled.insert(*verse.reference(), day);
+                let completed = done.contains(&(day, *verse.reference())) as usize;
+                for completion in [
+                    phases.entry(phase).or_default(),
+                    months.entry(month).or_default(),
+                ] {
+                    completion.scheduled += 1;
+                    completion.completed += completed;
+                }
+            }
+        }
+
+        // A verse is overdue once `for_day` has scheduled it since its last
+        // review, however long its interval is.
+        let overdue = scheduler
+            .intervals()
+            .into_iter()
+            .filter_map(|(phase, _, verse)| {
+                let last_reviewed = log
+                    .history(verse.reference())
+                    .filter(|review| review.outcome() == Outcome::Reviewed && review.date() < date)
+                    .map(|review| review.date())
+                    .max();
+                let missed = last_scheduled
+                    .get(verse.reference())
+                    .is_some_and(|scheduled| Some(*scheduled) > last_reviewed);
+                let started = date - Days::new(verse.days_in().max(0) as u64);
+                let since = last_reviewed.unwrap_or(first.max(started));
+                let days = (date - since).num_days();
+                missed.then(|| Overdue {
+                    reference: *verse.reference(),
+                    phase,
+                    last_reviewed,
+                    days,
+                })
+            })
+            .collect();
+
+        Self {
+            current_streak,
+            longest_streak,
+            phases,
+            months,
+            overdue,
+        }
+    }
+}
+
+/// The current and longest runs of consecutive days with a review. The
+/// current run may end on `date` or, if nothing has been reviewed yet that
+/// day, the day before.
+fn streaks(log: &ReviewLog, date: NaiveDate) -> (usize, usize) {
+    let days = log.completed_days();
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &day in days.range(..=date) {
+        run = match previous {
+            Some(previous) if (day - previous).num_days() == 1 => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+    let yesterday = date - Days::new(1);
+    let current = match previous {
+        Some(last) if last == date || last == yesterday => run,
+        _ => 0,
+    };
+    (current, longest)
+}
+
+impl fmt::Display for Adherence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Current streak: {} day(s)", self.current_streak)?;
+        writeln!(f, "Longest streak: {} day(s)", self.longest_streak)?;
+        writeln!(f, "\nCompleted by phase:")?;
+        for (phase, completion) in &self.phases {
+            writeln!(f, "  {:<8} {completion}", phase.to_string())?;
+        }
+        writeln!(f, "\nCompleted by month:")?;
+        for (start, completion) in &self.months {
+            writeln!(f, "  {start}  {completion}")?;
+        }
+        writeln!(f, "\nOverdue:")?;
+        if self.overdue.is_empty() {
+            writeln!(f, "  none")?;
+        }
+        for overdue in &self.overdue {
+            let last = match overdue.last_reviewed {
+                Some(date) => format!("last reviewed {date}, {} days ago", overdue.days),
+                None => format!("not reviewed in {} days", overdue.days),
+            };
+            writeln!(f, "  {} ({}): {last}", overdue.reference, overdue.phase)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::review::Review;
+    use crate::srs::{Sm2, SpacedRepetition};
+    use crate::tests::weekly_entries;
+    use crate::{Book, MonthMode, ScheduledVerses, VerseEntry};
+
+    #[test]
+    fn overdue_follows_the_scheduler() {
+        let start = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let reference = Reference::new(Book::John, 1, 1);
+        let entries = [VerseEntry::from_date(start, reference)];
+        let mut log = ReviewLog::default();
+        for day in [0, 1, 7] {
+            let date = start + Days::new(day);
+            log.record(Review::new(
+                date,
+                reference,
+                Frequency::Daily,
+                Outcome::Reviewed,
+            ));
+        }
+        let config = ScheduleConfig::default();
+        let frost = |date| {
+            let for_day = |day| ScheduledVerses::from_date(day, &entries).for_today();
+            let scheduler = ScheduledVerses::from_date(date, &entries);
+            Adherence::new(&log, &scheduler, &config, start, for_day).overdue
+        };
+        let sm2 = |date| {
+            let for_day = |day| SpacedRepetition::new::<Sm2>(day, &entries, &[], &log).for_today();
+            let scheduler = SpacedRepetition::new::<Sm2>(date, &entries, &[], &log);
+            Adherence::new(&log, &scheduler, &config, start, for_day).overdue
+        };
+
+        // A week after the last review, Frost's daily phase has long been
+        // missed, but SM-2's 15-day interval hasn't run out.
+        let date = start + Days::new(14);
+        let overdue = frost(date);
+        assert_eq!(overdue.len(), 1);
+        assert_eq!((overdue[0].phase, overdue[0].days), (Frequency::Daily, 7));
+        assert!(sm2(date).is_empty());
+
+        let overdue = sm2(start + Days::new(30));
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].phase, Frequency::Weekly);
+        assert_eq!(overdue[0].last_reviewed, Some(start + Days::new(7)));
+    }
+
+    #[test]
+    fn nothing_is_overdue_after_every_scheduled_review() {
+        let log_start = NaiveDate::from_ymd_opt(2032, 6, 1).unwrap();
+        // Well into their monthly phase, which reviews them 27 to 31 days
+        // apart rather than every 28.
+        let entries = weekly_entries(log_start - Days::new(7 * 40), 10);
+        for month_mode in [MonthMode::FourWeek, MonthMode::Calendar] {
+            let config = ScheduleConfig {
+                month_mode,
+                ..ScheduleConfig::frost()
+            };
+            let schedule = |date| ScheduledVerses::from_date(date, &entries).with_config(config);
+            let for_day = |date| schedule(date).for_today();
+
+            let end = log_start + Days::new(150);
+            let mut log = ReviewLog::default();
+            for day in log_start.iter_days().take_while(|day| *day < end) {
+                log.mark_all_done(&for_day(day), Outcome::Reviewed);
+            }
+            for date in (log_start + Days::new(60))
+                .iter_days()
+                .take_while(|day| *day <= end)
+            {
+                let scheduler = schedule(date);
+                let monthly = scheduler
+                    .intervals()
+                    .into_iter()
+                    .filter(|(phase, _, _)| *phase == Frequency::Monthly);
+                assert!(monthly.count() > 0);
+                let adherence = Adherence::new(&log, &scheduler, &config, log_start, for_day);
+                assert_eq!(adherence.overdue, [], "{month_mode:?} on {date}");
+            }
+        }
+    }
+
+    #[test]
+    fn adherence_counts_streaks_and_completed_reviews() {
+        let start = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let entries = (1..=3)
+            .map(|verse| VerseEntry::from_date(start, Reference::new(Book::John, 1, verse)))
+            .collect_vec();
+        let for_day = |date| ScheduledVerses::from_date(date, &entries).for_today();
+
+        let mut log = ReviewLog::default();
+        for day in [0, 1, 2, 4] {
+            log.mark_all_done(&for_day(start + Days::new(day)), Outcome::Reviewed);
+        }
+        let today = start + Days::new(6);
+        let verses = ScheduledVerses::from_date(today, &entries);
+        let adherence = Adherence::new(&log, &verses, verses.config(), start, for_day);
+
+        assert_eq!(adherence.current_streak, 0);
+        assert_eq!(adherence.longest_streak, 3);
+        let daily = Completion {
+            scheduled: 18,
+            completed: 12,
+        };
+        assert_eq!(adherence.phases[&Frequency::Daily], daily);
+        assert_eq!(adherence.months.values().copied().collect_vec(), [daily]);
+        assert_eq!(adherence.overdue.len(), 3);
+        assert!(adherence.overdue.iter().all(|overdue| overdue.days == 2));
+    }
+}
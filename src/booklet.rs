@@ -0,0 +1,246 @@
+//! Printable HTML booklet for a month: one page per week, with a checkbox for
+//! every verse to review on each day.
+
+use std::fmt::Write;
+
+use crate::{Verse, VersesForADay};
+
+const STYLE: &str = r#"
+* { box-sizing: border-box; }
+body { font-family: Georgia, "Times New Roman", serif; margin: 1.5rem; color: #111; }
+h1 { font-size: 1.4rem; margin: 0 0 1rem; }
+h2 { font-size: 1.1rem; margin: 0 0 .5rem; }
+.week { margin-bottom: 2rem; }
+.days { display: grid; grid-template-columns: repeat(7, 1fr); gap: .4rem; }
+.day { border: 1px solid #999; border-radius: 4px; padding: .4rem; font-size: .8rem; min-height: 12rem; }
+.day h3 { font-size: .85rem; margin: 0 0 .3rem; border-bottom: 1px solid #ccc; }
+.day h4 { font-size: .7rem; text-transform: uppercase; letter-spacing: .05em; margin: .4rem 0 .1rem; color: #555; }
+.day ul { list-style: none; margin: 0; padding: 0; }
+.day li { display: flex; gap: .3rem; align-items: baseline; }
+.day input { margin: 0; }
+@media print {
+  @page { size: landscape; margin: 1cm; }
+  body { margin: 0; }
+  .week { break-after: page; page-break-after: always; margin: 0; }
+  .week:last-child { break-after: auto; page-break-after: auto; }
+  .day { break-inside: avoid; }
+}
+"#;
+
+/// Renders `weeks` of days as a standalone HTML page with no external
+/// assets. Each week is printed on its own page, and a short first week is
+/// pushed to the right so every day stays under its weekday.
+pub fn render<'b, 'a: 'b>(
+    weeks: impl IntoIterator<Item = &'b [VersesForADay<'a>]>,
+    title: &str,
+) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(html, "<title>{}</title>", escape(title));
+    let _ = writeln!(html, "<style>{STYLE}</style>\n</head>\n<body>");
+    for (w, week) in weeks.into_iter().enumerate() {
+        html.push_str("<section class=\"week\">\n");
+        if w == 0 {
+            let _ = writeln!(html, "<h1>{}</h1>", escape(title));
+        }
+        let _ = writeln!(html, "<h2>Week {}</h2>\n<div class=\"days\">", w + 1);
+        if w == 0 {
+            for _ in week.len()..7 {
+                html.push_str("<div class=\"blank\"></div>\n");
+            }
+        }
+        for (d, day) in week.iter().enumerate() {
+            render_day(&mut html, d, day);
+        }
+        html.push_str("</div>\n</section>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn render_day(html: &mut String, d: usize, day: &VersesForADay) {
+    let heading = match day.date() {
+        Some(date) => date.format("%A, %b %-d").to_string(),
+        None => format!("Day {}", d + 1),
+    };
+    let _ = writeln!(html, "<div class=\"day\">\n<h3>{heading}</h3>");
+    for (name, verses) in [
+        ("Daily", day.daily()),
+        ("Weekly", day.weekly()),
+        ("Monthly", day.monthly()),
+        ("Yearly", day.yearly()),
+    ] {
+        if verses.is_empty() {
+            continue;
+        }
+        let _ = writeln!(html, "<h4>{name}</h4>\n<ul>");
+        for verse in verses {
+            render_verse(html, verse, None);
+        }
+        html.push_str("</ul>\n");
+    }
+    if !day.carried_over().is_empty() {
+        html.push_str("<h4>Carried over</h4>\n<ul>\n");
+        for carried in day.carried_over() {
+            let missed = carried.missed().format("from %b %-d").to_string();
+            render_verse(html, carried.verse(), Some(&missed));
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</div>\n");
+}
+
+fn render_verse(html: &mut String, verse: &Verse, note: Option<&str>) {
+    let note = note
+        .map(|note| format!(" <small>({})</small>", escape(note)))
+        .unwrap_or_default();
+    let _ = writeln!(
+        html,
+        "<li><input type=\"checkbox\"><span>{}{note}</span></li>",
+        escape(&verse.reference().to_string())
+    );
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use chrono::{Days, NaiveDate, Weekday};
+
+    use super::*;
+    use crate::{
+        Book, CatchUp, Reference, ScheduleConfig, ScheduledVerses, VerseEntry,
+        VersesForACalendarMonth, VersesForAMonth,
+    };
+
+    #[test]
+    fn one_cell_per_day_and_a_checkbox_per_verse() {
+        let today = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let entries = (0..30)
+            .map(|i| {
+                let reference = Reference::range(Book::Psalms, 119, 2 * i + 1, 2 * i + 2);
+                VerseEntry::from_date(today - Days::new(5 * u64::from(i)), reference)
+            })
+            .collect::<Vec<_>>();
+        let month = ScheduledVerses::from_date(today, &entries).monthly_schedule();
+        let html = render(
+            month.weeks().iter().map(|week| week.days()),
+            "Psalm 119 <Aleph & Beth>",
+        );
+
+        assert_eq!(html.matches("<section class=\"week\">").count(), 4);
+        assert_eq!(html.matches("<div class=\"day\">").count(), 4 * 7);
+
+        let verses = month
+            .weeks()
+            .iter()
+            .flat_map(|week| week.days())
+            .map(|day| day.items().count())
+            .sum::<usize>();
+        assert!(verses > 0);
+        assert_eq!(html.matches("<input type=\"checkbox\">").count(), verses);
+        assert!(html.contains("<span>Psalm 119:1-2</span>"));
+
+        assert!(html.contains("<title>Psalm 119 &lt;Aleph &amp; Beth&gt;</title>"));
+        assert!(!html.contains("<Aleph"));
+        assert!(!html.contains("src="));
+        assert!(!html.contains("href="));
+    }
+
+    #[test]
+    fn carried_over_verses_get_their_own_section() {
+        let start = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        // Started well before, so they're reviewed weekly.
+        let entries = (1..=7)
+            .map(|i| {
+                let reference = Reference::new(Book::John, 1, i);
+                VerseEntry::from_date(start - Days::new(100), reference)
+            })
+            .collect::<Vec<_>>();
+        // Only the first day was reviewed, so every later one is missed.
+        let completed = [start].into_iter().collect::<BTreeSet<_>>();
+        let for_day = |date| {
+            ScheduledVerses::from_date(date, &entries)
+                .with_catch_up(CatchUp::new(completed.clone(), 1))
+                .for_today()
+        };
+        let month = VersesForAMonth::from_days(start, for_day);
+        let html = render(month.weeks().iter().map(|week| week.days()), "John 1");
+
+        let carried = month
+            .weeks()
+            .iter()
+            .flat_map(|week| week.days())
+            .flat_map(|day| day.carried_over())
+            .collect::<Vec<_>>();
+        assert!(!carried.is_empty());
+        assert_eq!(html.matches("<small>(from ").count(), carried.len());
+        let missed = carried[0].missed().format("%b %-d");
+        let reference = carried[0].verse().reference();
+        let item = format!("<span>{reference} <small>(from {missed})</small></span>");
+        assert!(html.contains(&item), "{html}");
+        let verses = month
+            .weeks()
+            .iter()
+            .flat_map(|week| week.days())
+            .map(|day| day.items().count())
+            .sum::<usize>();
+        assert_eq!(html.matches("<input type=\"checkbox\">").count(), verses);
+    }
+
+    #[test]
+    fn calendar_months_print_every_day_from_the_week_start() {
+        // March 2027 starts on a Monday.
+        let first = NaiveDate::from_ymd_opt(2027, 3, 1).unwrap();
+        let entries = (0..30)
+            .map(|i| {
+                let reference = Reference::new(Book::Psalms, 119, i + 1);
+                VerseEntry::from_date(first - Days::new(7 * u64::from(i)), reference)
+            })
+            .collect::<Vec<_>>();
+        let config = ScheduleConfig {
+            week_start: Weekday::Sun,
+            ..ScheduleConfig::frost()
+        };
+        let for_day = |date| {
+            ScheduledVerses::from_date(date, &entries)
+                .with_config(config)
+                .for_today()
+        };
+        let month = VersesForACalendarMonth::from_days(first, &config, for_day);
+        let html = render(month.weeks(), "March 2027");
+
+        assert_eq!(html.matches("<div class=\"day\">").count(), 31);
+        assert!(html.contains("<h3>Wednesday, Mar 31</h3>"));
+        // Mar 1-6, 7-13, 14-20, 21-27 and 28-31, with Sunday left blank in
+        // the first.
+        assert_eq!(html.matches("<section class=\"week\">").count(), 5);
+        assert_eq!(html.matches("<div class=\"blank\">").count(), 1);
+        let weeks = html.split("<h2>").skip(1).collect::<Vec<_>>();
+        assert!(weeks[1].contains("<h3>Sunday, Mar 7</h3>"));
+        assert!(!weeks[1].contains("Mar 14"));
+
+        let verses = month
+            .days()
+            .iter()
+            .map(|day| day.items().count())
+            .sum::<usize>();
+        assert!(verses > 0);
+        assert_eq!(html.matches("<input type=\"checkbox\">").count(), verses);
+    }
+
+    #[test]
+    fn markup_in_text_is_escaped() {
+        assert_eq!(
+            escape(r#"<a href="x">&</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+    }
+}
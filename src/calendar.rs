@@ -0,0 +1,288 @@
+//! iCalendar (RFC 5545) export of the review schedule, so each day's verses
+//! show up as an all-day event in calendar apps.
+
+use chrono::{Days, NaiveDate, NaiveDateTime};
+use itertools::Itertools;
+
+use crate::VersesForADay;
+
+const PRODID: &str = "-//scripture_retention_algorithm//Review Schedule//EN";
+
+/// Writes one all-day event per day from `from` to `to`, inclusive, listing
+/// the verses a [`Scheduler`](crate::Scheduler) assigns to it.
+///
+/// Each event's UID is derived from its date and `uid_domain`, so importing
+/// a new export over an old one updates the existing events rather than
+/// adding copies.
+#[derive(Clone, Debug)]
+pub struct CalendarExport<'a> {
+    days: Vec<(NaiveDate, VersesForADay<'a>)>,
+    stamp: NaiveDateTime,
+    uid_domain: String,
+}
+
+impl<'a> CalendarExport<'a> {
+    /// Lists the verses `for_day` schedules on each day from `from` to `to`,
+    /// inclusive. `stamp` is the UTC time the export is made, written as each
+    /// event's `DTSTAMP`.
+    pub fn new(
+        from: NaiveDate,
+        to: NaiveDate,
+        stamp: NaiveDateTime,
+        for_day: impl Fn(NaiveDate) -> VersesForADay<'a>,
+    ) -> Self {
+        let days = from
+            .iter_days()
+            .take_while(|date| *date <= to)
+            .map(|date| (date, for_day(date)))
+            .collect();
+        Self {
+            days,
+            stamp,
+            uid_domain: "scripture-retention-algorithm".to_string(),
+        }
+    }
+
+    /// Keeps the UIDs of separate plans apart when they're imported into the
+    /// same calendar.
+    pub fn with_uid_domain(mut self, domain: impl Into<String>) -> Self {
+        self.uid_domain = domain.into();
+        self
+    }
+
+    /// The calendar as an `.ics` file, with CRLF line endings and long lines
+    /// folded. Days with nothing to review are left out.
+    pub fn to_ics(&self) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!("PRODID:{PRODID}"),
+            "CALSCALE:GREGORIAN".to_string(),
+            "METHOD:PUBLISH".to_string(),
+        ];
+        for (date, day) in &self.days {
+            lines.extend(self.event(*date, day));
+        }
+        lines.push("END:VCALENDAR".to_string());
+        lines.iter().map(|line| fold(line)).collect()
+    }
+
+    fn event(&self, date: NaiveDate, day: &VersesForADay) -> Vec<String> {
+        let carried_over = day
+            .carried_over()
+            .iter()
+            .map(|c| c.verse().clone())
+            .collect_vec();
+        let sections = [
+            ("Daily", day.daily()),
+            ("Weekly", day.weekly()),
+            ("Monthly", day.monthly()),
+            ("Yearly", day.yearly()),
+            ("Carried over", &carried_over),
+        ];
+        if sections.iter().all(|(_, verses)| verses.is_empty()) {
+            return vec![];
+        }
+        let mut summary = format!(
+            "Scripture review: {} daily, {} weekly, {} monthly",
+            day.daily().len(),
+            day.weekly().len(),
+            day.monthly().len()
+        );
+        if !day.yearly().is_empty() {
+            summary += &format!(", {} yearly", day.yearly().len());
+        }
+        let description = sections
+            .iter()
+            .filter(|(name, verses)| {
+                !verses.is_empty() || !matches!(*name, "Yearly" | "Carried over")
+            })
+            .map(|(name, verses)| {
+                format!(
+                    "{name}: {}",
+                    verses.iter().map(|v| v.reference()).join(", ")
+                )
+            })
+            .join("\n");
+        let next_day = date + Days::new(1);
+        vec![
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}@{}", date.format("%Y%m%d"), self.uid_domain),
+            format!("DTSTAMP:{}", self.stamp.format("%Y%m%dT%H%M%SZ")),
+            format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+            format!("DTEND;VALUE=DATE:{}", next_day.format("%Y%m%d")),
+            format!("SUMMARY:{}", escape(&summary)),
+            format!("DESCRIPTION:{}", escape(&description)),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]
+    }
+}
+
+/// Escapes a TEXT value (RFC 5545 §3.3.11).
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Ends `line` with CRLF, splitting it so no line is longer than 75 octets
+/// (RFC 5545 §3.1). Continuation lines start with a space.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, CatchUp, Plan, Reference, ScheduleConfig, ScheduledVerses, VerseEntry};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2030, 1, day).unwrap()
+    }
+
+    fn plan() -> Plan {
+        let entries = (1..=3)
+            .map(|i| VerseEntry::from_date(date(i), Reference::new(Book::John, 3, 15 + i as u16)))
+            .collect();
+        Plan::new(entries)
+    }
+
+    fn stamp(hour: u32) -> NaiveDateTime {
+        date(1).and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn export(
+        plan: &Plan,
+        from: NaiveDate,
+        to: NaiveDate,
+        stamp: NaiveDateTime,
+    ) -> CalendarExport<'_> {
+        CalendarExport::new(from, to, stamp, |date| plan.schedule(date).for_today())
+    }
+
+    #[test]
+    fn text_values_are_escaped() {
+        assert_eq!(escape("a,b;c\\d\ne"), r"a\,b\;c\\d\ne");
+        assert_eq!(escape("John 3:16-18"), "John 3:16-18");
+    }
+
+    #[test]
+    fn long_lines_are_folded_at_75_octets() {
+        let line = format!("DESCRIPTION:{}", "é".repeat(60));
+        let folded = fold(&line);
+        for part in folded.split_terminator("\r\n") {
+            assert!(part.len() <= 75, "{part:?} is {} octets", part.len());
+        }
+        // Unfolding (removing each CRLF and the space after it) gives the
+        // line back, so no character was split.
+        assert_eq!(folded.replace("\r\n ", "").trim_end(), line);
+        assert_eq!(fold("SHORT"), "SHORT\r\n");
+        assert_eq!(fold(&"x".repeat(75)).matches("\r\n").count(), 1);
+        assert_eq!(fold(&"x".repeat(76)).matches("\r\n").count(), 2);
+    }
+
+    #[test]
+    fn every_line_ends_with_crlf() {
+        let plan = plan();
+        let ics = export(&plan, date(1), date(7), stamp(9)).to_ics();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches('\n').count(), ics.matches("\r\n").count());
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 7);
+    }
+
+    #[test]
+    fn uids_depend_only_on_the_date_and_domain() {
+        let plan = plan();
+        let uids = |export: CalendarExport| {
+            export
+                .to_ics()
+                .lines()
+                .filter_map(|line| line.strip_prefix("UID:"))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        let first = uids(export(&plan, date(1), date(7), stamp(9)));
+        let later = uids(export(&plan, date(4), date(10), stamp(21)));
+        assert_eq!(first[0], "20300101@scripture-retention-algorithm");
+        assert_eq!(first[3..], later[..4]);
+
+        let other = uids(export(&plan, date(1), date(7), stamp(9)).with_uid_domain("psalms"));
+        assert_eq!(other[0], "20300101@psalms");
+    }
+
+    #[test]
+    fn events_list_whatever_the_scheduler_assigns() {
+        // Started well before January, so they're reviewed weekly.
+        let started = date(1) - Days::new(100);
+        let entries = (1..=7)
+            .map(|i| VerseEntry::from_date(started, Reference::new(Book::John, 1, i)))
+            .collect();
+        let plan = Plan::new(entries);
+        let completed: std::collections::BTreeSet<_> = [date(20)].into_iter().collect();
+        let for_day = |date| {
+            plan.schedule(date)
+                .with_catch_up(CatchUp::new(completed.clone(), 1))
+                .for_today()
+        };
+        let weekly = |date| plan.schedule(date).for_today().weekly().len();
+        let missed = (21..=31).map(date).find(|day| weekly(*day) > 0).unwrap();
+        let next = missed + Days::new(1);
+
+        let ics = CalendarExport::new(next, next, stamp(9), for_day).to_ics();
+        assert!(ics.contains("Carried over: John"), "{ics}");
+
+        let nothing = CalendarExport::new(date(1), date(7), stamp(9), |_| VersesForADay::default());
+        assert!(!nothing.to_ics().contains("BEGIN:VEVENT"));
+    }
+
+    #[test]
+    fn summaries_count_yearly_verses_only_when_there_are_some() {
+        let entries = [VerseEntry::from_date(
+            date(6),
+            Reference::new(Book::John, 3, 16),
+        )];
+        let config = ScheduleConfig {
+            daily_weeks: 1,
+            weekly_weeks: 1,
+            monthly_weeks: 1,
+            yearly_weeks: Some(3),
+            ..ScheduleConfig::frost()
+        };
+        let for_day = |date| {
+            ScheduledVerses::from_date(date, &entries)
+                .with_config(config)
+                .for_today()
+        };
+        let ics = CalendarExport::new(date(6), date(6) + Days::new(83), stamp(9), for_day).to_ics();
+        let summaries = ics
+            .lines()
+            .filter(|line| line.starts_with("SUMMARY:"))
+            .collect_vec();
+        // Weeks 3, 6 and 9 after the verse started.
+        let yearly = summaries
+            .iter()
+            .filter(|line| line.ends_with(r"\, 1 yearly"));
+        assert_eq!(yearly.count(), 3, "{summaries:#?}");
+        assert_eq!(
+            summaries
+                .iter()
+                .filter(|line| line.contains("yearly"))
+                .count(),
+            3
+        );
+    }
+}
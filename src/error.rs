@@ -0,0 +1,220 @@
+use std::fmt;
+
+use crate::parser::Span;
+use crate::reference::ReferenceError;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The date before the separator isn't a valid `YYYY-MM-DD` date.
+    InvalidDate {
+        span: Span,
+        text: String,
+    },
+    /// The line has no `|` between the date and the reference.
+    MissingSeparator {
+        span: Span,
+        text: String,
+    },
+    /// Nothing follows the separator.
+    EmptyReference {
+        span: Span,
+        text: String,
+    },
+    /// The reference was already scheduled on an earlier line.
+    DuplicateEntry {
+        span: Span,
+        text: String,
+    },
+    /// The reference doesn't start with the name of a book of the Bible.
+    UnknownBook {
+        span: Span,
+        text: String,
+    },
+    /// The chapter and verse after the book aren't valid.
+    InvalidReference {
+        span: Span,
+        text: String,
+    },
+    /// The book doesn't have that chapter, or the chapter doesn't have that
+    /// verse.
+    NoSuchVerse {
+        span: Span,
+        text: String,
+    },
+    /// A pause isn't a valid `YYYY-MM-DD..YYYY-MM-DD` range.
+    InvalidPause {
+        span: Span,
+        text: String,
+    },
+    /// `@month-mode` isn't followed by `four-week` or `calendar`.
+    InvalidMonthMode {
+        span: Span,
+        text: String,
+    },
+    /// The line starts with `@` but isn't a known directive.
+    UnknownDirective {
+        span: Span,
+        text: String,
+    },
+    /// A directive that can only be given once was repeated.
+    DuplicateDirective {
+        span: Span,
+        text: String,
+    },
+    /// A line of the review log isn't a valid review.
+    InvalidReview {
+        span: Span,
+        text: String,
+    },
+    /// A date range ends before it starts.
+    ReversedRange {
+        from: chrono::NaiveDate,
+        to: chrono::NaiveDate,
+    },
+    /// Every bad line found while loading in strict mode.
+    Invalid(Vec<Error>),
+    Reference(ReferenceError),
+    Date(chrono::ParseError),
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl Error {
+    /// Where in the verse list this error was found, if it came from one.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Error::InvalidDate { span, .. }
+            | Error::MissingSeparator { span, .. }
+            | Error::EmptyReference { span, .. }
+            | Error::DuplicateEntry { span, .. }
+            | Error::UnknownBook { span, .. }
+            | Error::InvalidReference { span, .. }
+            | Error::NoSuchVerse { span, .. }
+            | Error::InvalidPause { span, .. }
+            | Error::InvalidMonthMode { span, .. }
+            | Error::UnknownDirective { span, .. }
+            | Error::DuplicateDirective { span, .. }
+            | Error::InvalidReview { span, .. } => Some(*span),
+            _ => None,
+        }
+    }
+
+    /// The 1-based line of the verse list this error refers to, if any.
+    pub fn line(&self) -> Option<usize> {
+        self.span().map(|span| span.line)
+    }
+
+    /// The full source line this error refers to, if any.
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Error::InvalidDate { text, .. }
+            | Error::MissingSeparator { text, .. }
+            | Error::EmptyReference { text, .. }
+            | Error::DuplicateEntry { text, .. }
+            | Error::UnknownBook { text, .. }
+            | Error::InvalidReference { text, .. }
+            | Error::NoSuchVerse { text, .. }
+            | Error::InvalidPause { text, .. }
+            | Error::InvalidMonthMode { text, .. }
+            | Error::UnknownDirective { text, .. }
+            | Error::DuplicateDirective { text, .. }
+            | Error::InvalidReview { text, .. } => Some(text),
+            _ => None,
+        }
+    }
+
+    /// A description of the problem without its location.
+    pub fn message(&self) -> String {
+        match self {
+            Error::InvalidDate { .. } => "invalid date, expected YYYY-MM-DD".to_string(),
+            Error::MissingSeparator { .. } => "missing `|` between date and reference".to_string(),
+            Error::EmptyReference { .. } => "empty reference".to_string(),
+            Error::DuplicateEntry { .. } => "reference is already scheduled".to_string(),
+            Error::UnknownBook { .. } => "unknown book".to_string(),
+            Error::InvalidReference { .. } => {
+                "expected `chapter:verse` or `chapter:verse-verse`".to_string()
+            }
+            Error::Invalid(errors) => format!("{} invalid line(s)", errors.len()),
+            Error::NoSuchVerse { .. } => "no such chapter or verse".to_string(),
+            Error::InvalidPause { .. } => {
+                "invalid pause, expected YYYY-MM-DD..YYYY-MM-DD".to_string()
+            }
+            Error::InvalidMonthMode { .. } => {
+                "invalid month mode, expected four-week or calendar".to_string()
+            }
+            Error::UnknownDirective { .. } => {
+                "unknown directive, expected `@start`, `@pause` or `@month-mode`".to_string()
+            }
+            Error::DuplicateDirective { .. } => "directive is already set".to_string(),
+            Error::InvalidReview { .. } => {
+                "invalid review, expected a JSON object with `date`, `reference`, `phase` and `outcome`"
+                    .to_string()
+            }
+            Error::ReversedRange { from, to } => {
+                format!("the range ends on {to}, before it starts on {from}")
+            }
+            Error::Reference(e) => e.to_string(),
+            Error::Date(e) => format!("invalid date: {e}"),
+            Error::Io(e) => e.to_string(),
+            Error::Json(e) => e.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Invalid(errors) => {
+                write!(f, "{}", self.message())?;
+                for error in errors {
+                    write!(f, "\n  {error}")?;
+                }
+                Ok(())
+            }
+            _ => match (self.span(), self.text()) {
+                (Some(span), Some(text)) => {
+                    write!(f, "line {span}: {}: `{}`", self.message(), text.trim())
+                }
+                _ => write!(f, "{}", self.message()),
+            },
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Reference(e) => Some(e),
+            Error::Date(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<chrono::ParseError> for Error {
+    fn from(e: chrono::ParseError) -> Self {
+        Error::Date(e)
+    }
+}
+
+impl From<ReferenceError> for Error {
+    fn from(e: ReferenceError) -> Self {
+        Error::Reference(e)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
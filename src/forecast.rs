@@ -0,0 +1,546 @@
+//! How many reviews the schedule asks for on each day and week of a date
+//! range, to see how the workload grows as verses move through their phases.
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate};
+use itertools::Itertools;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::{Frequency, ScheduleConfig, VersesForADay};
+
+/// How many verses are reviewed in each phase.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Load {
+    pub daily: usize,
+    pub weekly: usize,
+    pub monthly: usize,
+    pub yearly: usize,
+}
+
+impl Load {
+    /// Counts the verses to review on `day`, carried-over ones included.
+    pub fn of(day: &VersesForADay) -> Self {
+        let mut load = Self::default();
+        for (phase, _) in day.items() {
+            match phase {
+                Frequency::Daily => load.daily += 1,
+                Frequency::Weekly => load.weekly += 1,
+                Frequency::Monthly => load.monthly += 1,
+                Frequency::Yearly => load.yearly += 1,
+                Frequency::NotStarted | Frequency::Done => {}
+            }
+        }
+        load
+    }
+
+    pub fn total(&self) -> usize {
+        self.daily + self.weekly + self.monthly + self.yearly
+    }
+
+    fn counts(&self) -> [usize; 5] {
+        [
+            self.daily,
+            self.weekly,
+            self.monthly,
+            self.yearly,
+            self.total(),
+        ]
+    }
+
+    fn add(&mut self, other: &Load) {
+        self.daily += other.daily;
+        self.weekly += other.weekly;
+        self.monthly += other.monthly;
+        self.yearly += other.yearly;
+    }
+}
+
+impl Serialize for Load {
+    /// Includes the total, e.g.
+    /// `{"daily":7,"weekly":2,"monthly":3,"yearly":0,"total":12}`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Load", 5)?;
+        state.serialize_field("daily", &self.daily)?;
+        state.serialize_field("weekly", &self.weekly)?;
+        state.serialize_field("monthly", &self.monthly)?;
+        state.serialize_field("yearly", &self.yearly)?;
+        state.serialize_field("total", &self.total())?;
+        state.end()
+    }
+}
+
+/// The reviews in a day or week of the forecast.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Bucket {
+    pub start: NaiveDate,
+    /// How many days of the range it covers: 1 for a day, and up to 7 for a
+    /// week, fewer at the ends of the range.
+    pub days: usize,
+    pub load: Load,
+}
+
+/// Mean counts per bucket, by phase.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct Average {
+    pub daily: f64,
+    pub weekly: f64,
+    pub monthly: f64,
+    pub yearly: f64,
+    pub total: f64,
+}
+
+/// Averages and peaks over a forecast's days or weeks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct Summary {
+    pub average: Average,
+    /// The highest count in each phase, not necessarily from the same bucket.
+    pub peak: Load,
+    /// The bucket with the most reviews in total.
+    pub busiest: Option<Bucket>,
+}
+
+impl Summary {
+    fn of<'b>(buckets: impl IntoIterator<Item = &'b Bucket>) -> Self {
+        let buckets = buckets.into_iter().collect_vec();
+        if buckets.is_empty() {
+            return Self::default();
+        }
+        let n = buckets.len() as f64;
+        let mean = |count: fn(&Load) -> usize| {
+            buckets.iter().map(|b| count(&b.load)).sum::<usize>() as f64 / n
+        };
+        let max = |count: fn(&Load) -> usize| buckets.iter().map(|b| count(&b.load)).max();
+        Self {
+            average: Average {
+                daily: mean(|load| load.daily),
+                weekly: mean(|load| load.weekly),
+                monthly: mean(|load| load.monthly),
+                yearly: mean(|load| load.yearly),
+                total: mean(Load::total),
+            },
+            peak: Load {
+                daily: max(|load| load.daily).unwrap_or_default(),
+                weekly: max(|load| load.weekly).unwrap_or_default(),
+                monthly: max(|load| load.monthly).unwrap_or_default(),
+                yearly: max(|load| load.yearly).unwrap_or_default(),
+            },
+            // The first of several equally busy buckets.
+            busiest: buckets
+                .iter()
+                .rev()
+                .max_by_key(|b| b.load.total())
+                .map(|b| **b),
+        }
+    }
+}
+
+/// Whether to show a forecast day by day or week by week.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Granularity {
+    Day,
+    #[default]
+    Week,
+}
+
+impl FromStr for Granularity {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "day" | "daily" => Ok(Granularity::Day),
+            "week" | "weekly" => Ok(Granularity::Week),
+            _ => Err(format!(
+                "unknown granularity `{name}`, expected day or week"
+            )),
+        }
+    }
+}
+
+/// Review counts for every day and week from one date to another.
+///
+/// Weeks start on the configured [`ScheduleConfig::week_start`]. The first and
+/// last weeks may be cut short by the range; they're listed but left out of
+/// the weekly averages and peaks unless no week is complete.
+#[derive(Clone, Debug, Serialize)]
+pub struct Forecast {
+    days: Vec<Bucket>,
+    weeks: Vec<Bucket>,
+    by_day: Summary,
+    by_week: Summary,
+}
+
+impl Forecast {
+    /// Counts the verses `for_day` schedules on each day from `from` to `to`,
+    /// inclusive.
+    pub fn new<'a>(
+        from: NaiveDate,
+        to: NaiveDate,
+        config: &ScheduleConfig,
+        for_day: impl Fn(NaiveDate) -> VersesForADay<'a>,
+    ) -> Self {
+        let days = from
+            .iter_days()
+            .take_while(|date| *date <= to)
+            .map(|date| Bucket {
+                start: date,
+                days: 1,
+                load: Load::of(&for_day(date)),
+            })
+            .collect_vec();
+        let mut weeks: Vec<Bucket> = vec![];
+        for day in &days {
+            let start = config.start_of_week(day.start);
+            match weeks.last_mut() {
+                Some(week) if week.start == start => {
+                    week.days += 1;
+                    week.load.add(&day.load);
+                }
+                _ => weeks.push(Bucket {
+                    start,
+                    days: 1,
+                    load: day.load,
+                }),
+            }
+        }
+        let full_weeks = weeks.iter().filter(|week| week.days == 7).collect_vec();
+        let by_week = if full_weeks.is_empty() {
+            Summary::of(&weeks)
+        } else {
+            Summary::of(full_weeks)
+        };
+        Self {
+            by_day: Summary::of(&days),
+            by_week,
+            days,
+            weeks,
+        }
+    }
+
+    pub fn days(&self) -> &[Bucket] {
+        &self.days
+    }
+
+    pub fn weeks(&self) -> &[Bucket] {
+        &self.weeks
+    }
+
+    pub fn buckets(&self, granularity: Granularity) -> &[Bucket] {
+        match granularity {
+            Granularity::Day => &self.days,
+            Granularity::Week => &self.weeks,
+        }
+    }
+
+    pub fn summary(&self, granularity: Granularity) -> &Summary {
+        match granularity {
+            Granularity::Day => &self.by_day,
+            Granularity::Week => &self.by_week,
+        }
+    }
+
+    /// A plain-text table with a row per day or week, followed by the
+    /// averages and peaks.
+    pub fn to_table(&self, granularity: Granularity) -> String {
+        let (heading, unit) = match granularity {
+            Granularity::Day => ("Day", "day"),
+            Granularity::Week => ("Week of", "week"),
+        };
+        let mut table = format!(
+            "{heading:<12} {:>7} {:>7} {:>7} {:>7} {:>7}\n",
+            "Daily", "Weekly", "Monthly", "Yearly", "Total"
+        );
+        for bucket in self.buckets(granularity) {
+            let mut row = format!("{:<12}", bucket.start.to_string());
+            for count in bucket.load.counts() {
+                let _ = write!(row, " {count:>7}");
+            }
+            if granularity == Granularity::Week && bucket.days < 7 {
+                let _ = write!(row, "  ({} of 7 days)", bucket.days);
+            }
+            table.push_str(&row);
+            table.push('\n');
+        }
+
+        let summary = self.summary(granularity);
+        let average = summary.average;
+        let _ = writeln!(
+            table,
+            "\n{:<12} {:>7.1} {:>7.1} {:>7.1} {:>7.1} {:>7.1}",
+            "Average",
+            average.daily,
+            average.weekly,
+            average.monthly,
+            average.yearly,
+            average.total
+        );
+        let mut peak = format!("{:<12}", "Peak");
+        for count in summary.peak.counts() {
+            let _ = write!(peak, " {count:>7}");
+        }
+        table.push_str(&peak);
+        table.push('\n');
+        if let Some(busiest) = summary.busiest {
+            let _ = writeln!(
+                table,
+                "\nBusiest {unit}: {} with {} reviews",
+                busiest.start,
+                busiest.load.total()
+            );
+        }
+        table
+    }
+
+    /// A standalone SVG stacked bar chart of the counts per day or week, with
+    /// a dashed line at the average total.
+    pub fn to_svg(&self, granularity: Granularity) -> String {
+        const HEIGHT: f64 = 320.0;
+        const LEFT: f64 = 48.0;
+        const TOP: f64 = 40.0;
+        const BOTTOM: f64 = 40.0;
+        const RIGHT: f64 = 16.0;
+        const PHASES: [(&str, &str); 4] = [
+            ("Daily", "#4e79a7"),
+            ("Weekly", "#f28e2b"),
+            ("Monthly", "#59a14f"),
+            ("Yearly", "#b07aa1"),
+        ];
+
+        let buckets = self.buckets(granularity);
+        let bar = match granularity {
+            Granularity::Day => 4.0,
+            Granularity::Week => 16.0,
+        };
+        let plot_width = (buckets.len() as f64 * bar).max(240.0);
+        let width = LEFT + plot_width + RIGHT;
+        let max = buckets.iter().map(|b| b.load.total()).max().unwrap_or(0);
+        let step = tick_step(max);
+        let top = (max.div_ceil(step) * step).max(step) as f64;
+        let y = |count: f64| TOP + HEIGHT - count / top * HEIGHT;
+
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{}" viewBox="0 0 {width} {}" font-family="sans-serif" font-size="11">"#,
+            TOP + HEIGHT + BOTTOM,
+            TOP + HEIGHT + BOTTOM
+        );
+        let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+        // Legend.
+        for (i, (name, color)) in PHASES.iter().enumerate() {
+            let x = LEFT + i as f64 * 80.0;
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{x}" y="12" width="10" height="10" fill="{color}"/><text x="{}" y="21">{name}</text>"#,
+                x + 14.0
+            );
+        }
+
+        // Horizontal grid lines and their counts.
+        for tick in (0..=top as usize).step_by(step) {
+            let ty = y(tick as f64);
+            let _ = writeln!(
+                svg,
+                r##"<line x1="{LEFT}" y1="{ty}" x2="{}" y2="{ty}" stroke="#ddd"/><text x="{}" y="{}" text-anchor="end">{tick}</text>"##,
+                LEFT + plot_width,
+                LEFT - 6.0,
+                ty + 4.0
+            );
+        }
+
+        for (i, bucket) in buckets.iter().enumerate() {
+            let x = LEFT + i as f64 * bar;
+            let mut base = 0.0;
+            svg.push_str("<g class=\"bar\">\n");
+            let counts = [
+                bucket.load.daily,
+                bucket.load.weekly,
+                bucket.load.monthly,
+                bucket.load.yearly,
+            ];
+            for (count, (name, color)) in counts.into_iter().zip(PHASES) {
+                if count == 0 {
+                    continue;
+                }
+                let height = count as f64 / top * HEIGHT;
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{x}" y="{}" width="{}" height="{height}" fill="{color}"><title>{}: {count} {}</title></rect>"#,
+                    y(base + count as f64),
+                    bar * 0.9,
+                    bucket.start,
+                    name.to_lowercase()
+                );
+                base += count as f64;
+            }
+            svg.push_str("</g>\n");
+            // Label the first bucket of each month.
+            let previous = i.checked_sub(1).map(|i| buckets[i].start.month());
+            if previous != Some(bucket.start.month()) {
+                let _ = writeln!(
+                    svg,
+                    r#"<text x="{x}" y="{}">{}</text>"#,
+                    TOP + HEIGHT + 16.0,
+                    bucket.start.format("%b %Y")
+                );
+            }
+        }
+
+        let average = y(self.summary(granularity).average.total);
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{LEFT}" y1="{average}" x2="{}" y2="{average}" stroke="#333" stroke-dasharray="4 3"><title>Average: {:.1}</title></line>"##,
+            LEFT + plot_width,
+            self.summary(granularity).average.total
+        );
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{LEFT}" y1="{TOP}" x2="{LEFT}" y2="{}" stroke="#333"/>"##,
+            TOP + HEIGHT
+        );
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// A round step between gridlines that gives at most about 8 of them.
+fn tick_step(max: usize) -> usize {
+    let mut step = 1;
+    loop {
+        for multiple in [1, 2, 5] {
+            if max <= step * multiple * 8 {
+                return step * multiple;
+            }
+        }
+        step *= 10;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Days;
+
+    use super::*;
+    use crate::ScheduledVerses;
+    use crate::tests::weekly_entries;
+
+    /// One new verse a week, starting on a Sunday, forecast for 12 weeks
+    /// from the Wednesday after, so the first week only has 4 days.
+    fn twelve_weeks(config: ScheduleConfig) -> Forecast {
+        let start = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let entries = weekly_entries(start, 20);
+        let for_day = |date| {
+            ScheduledVerses::from_date(date, &entries)
+                .with_config(config)
+                .for_today()
+        };
+        let from = start + Days::new(3);
+        let to = start + Days::new(7 * 12 - 1);
+        Forecast::new(from, to, &config, for_day)
+    }
+
+    #[test]
+    fn forecast_sums_days_into_weeks() {
+        let config = ScheduleConfig::frost();
+        let forecast = twelve_weeks(config);
+
+        assert_eq!(forecast.days().len(), 7 * 12 - 3);
+        assert_eq!(forecast.weeks().len(), 12);
+        assert_eq!(forecast.weeks()[0].days, 4);
+        for week in forecast.weeks() {
+            let days = forecast
+                .days()
+                .iter()
+                .filter(|day| config.start_of_week(day.start) == week.start);
+            let total: usize = days.map(|day| day.load.total()).sum();
+            assert_eq!(week.load.total(), total);
+        }
+
+        // Seven verses are in their daily phase from the seventh week on.
+        let by_day = forecast.summary(Granularity::Day);
+        assert_eq!(by_day.peak.daily, 7);
+        let busiest = by_day.busiest.unwrap();
+        let most = forecast.days().iter().map(|day| day.load.total()).max();
+        assert_eq!(Some(busiest.load.total()), most);
+        let by_week = forecast.summary(Granularity::Week);
+        assert_eq!(by_week.peak.daily, 49);
+    }
+
+    #[test]
+    fn table_lists_each_week_then_the_average_and_peak() {
+        let forecast = twelve_weeks(ScheduleConfig::frost());
+        let table = forecast.to_table(Granularity::Week);
+        let rows = table.lines().collect_vec();
+        let columns = |row: &str| row.split_whitespace().map(str::to_string).collect_vec();
+
+        assert_eq!(
+            columns(rows[0]),
+            [
+                "Week", "of", "Daily", "Weekly", "Monthly", "Yearly", "Total"
+            ]
+        );
+        let weeks = forecast.weeks();
+        for (row, week) in rows[1..=weeks.len()].iter().zip(weeks) {
+            let columns = columns(row);
+            assert_eq!(columns[0], week.start.to_string());
+            let counts = columns[1..6]
+                .iter()
+                .map(|c| c.parse::<usize>().unwrap())
+                .collect_vec();
+            assert_eq!(counts, week.load.counts());
+        }
+        assert!(rows[1].ends_with("(4 of 7 days)"));
+        assert!(!rows[2].contains("of 7 days"));
+
+        let summary = forecast.summary(Granularity::Week);
+        let rest = &rows[weeks.len() + 1..];
+        assert_eq!(rest[0], "");
+        let average = columns(rest[1]);
+        assert_eq!(average[0], "Average");
+        assert_eq!(average[5], format!("{:.1}", summary.average.total));
+        let peak = columns(rest[2]);
+        assert_eq!(peak[0], "Peak");
+        let counts = peak[1..]
+            .iter()
+            .map(|c| c.parse::<usize>().unwrap())
+            .collect_vec();
+        assert_eq!(counts, summary.peak.counts());
+        let busiest = summary.busiest.unwrap();
+        assert_eq!(
+            rest[4],
+            format!(
+                "Busiest week: {} with {} reviews",
+                busiest.start,
+                busiest.load.total()
+            )
+        );
+    }
+
+    #[test]
+    fn svg_is_well_formed_with_a_bar_per_bucket() {
+        let forecast = twelve_weeks(ScheduleConfig::frost());
+        for granularity in [Granularity::Week, Granularity::Day] {
+            let svg = forecast.to_svg(granularity);
+            assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+            assert!(svg.ends_with("</svg>\n"));
+
+            // Every opened tag is closed, in order.
+            let mut open = vec![];
+            for tag in svg.split('<').skip(1) {
+                let tag = &tag[..tag.find('>').unwrap()];
+                if let Some(name) = tag.strip_prefix('/') {
+                    assert_eq!(open.pop(), Some(name), "{svg}");
+                } else if !tag.ends_with('/') {
+                    open.push(tag.split_whitespace().next().unwrap());
+                }
+            }
+            assert!(open.is_empty(), "{open:?} left open");
+
+            let buckets = forecast.buckets(granularity).len();
+            assert_eq!(svg.matches("<g class=\"bar\">").count(), buckets);
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! Memorization plan generator: turns a passage into a verse list that starts
+//! a steady number of new verses each week.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Days, NaiveDate};
+
+use crate::reference::ReferenceError;
+use crate::versification::Versification;
+use crate::{Book, Plan, Reference, VerseEntry};
+
+/// A run of consecutive verses that may cross chapters and books, such as
+/// `John 1:1 - John 21:25`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Passage {
+    start: Reference,
+    end: Reference,
+    versification: Versification,
+}
+
+impl Passage {
+    /// The verses from `start` through `end`, inclusive. Fails if either
+    /// doesn't exist or `end` comes before `start`.
+    pub fn new(start: Reference, end: Reference) -> Result<Self, ReferenceError> {
+        Self::with_versification(start, end, Versification::default())
+    }
+
+    /// Like [`Passage::new`], but counting chapters and verses with
+    /// `versification` instead of the KJV.
+    pub fn with_versification(
+        start: Reference,
+        end: Reference,
+        versification: Versification,
+    ) -> Result<Self, ReferenceError> {
+        // Ranged references are narrowed to their first and last verse.
+        let start = Reference::new(start.book(), start.chapter(), start.verse());
+        let end = Reference::new(
+            end.book(),
+            end.chapter(),
+            end.end_verse().unwrap_or(end.verse()),
+        );
+        for reference in [start, end] {
+            if !versification.contains(&reference) {
+                return Err(ReferenceError::NoSuchVerse(reference.to_string()));
+            }
+        }
+        if end < start {
+            return Err(ReferenceError::InvalidVerse(format!("{start} - {end}")));
+        }
+        Ok(Self {
+            start,
+            end,
+            versification,
+        })
+    }
+
+    /// Every chapter of `book`.
+    pub fn book(book: Book, versification: Versification) -> Self {
+        let last_chapter = versification.chapters(book);
+        let last_verse = versification.verses(book, last_chapter).unwrap_or(1);
+        Self {
+            start: Reference::new(book, 1, 1),
+            end: Reference::new(book, last_chapter, last_verse),
+            versification,
+        }
+    }
+
+    /// Accepts a whole book (`John`), a reference (`Romans 8:28-30`), or two
+    /// references separated by a dash, where the second may leave out the
+    /// book (`John 1:1 - John 21:25`, `John 7:53-8:11`).
+    pub fn parse(text: &str, versification: Versification) -> Result<Self, ReferenceError> {
+        let text = text.trim();
+        if let Some(book) = Book::parse(text) {
+            return Ok(Passage::book(book, versification));
+        }
+        if let Ok(reference) = text.parse::<Reference>() {
+            return Passage::with_versification(reference, reference, versification);
+        }
+        let Some((start, end)) = text.rsplit_once(['-', '–']) else {
+            return Err(ReferenceError::InvalidVerse(text.to_string()));
+        };
+        let start: Reference = start.parse()?;
+        let end = match end.parse::<Reference>() {
+            Ok(end) => end,
+            Err(_) => format!("{} {}", start.book().name(), end.trim()).parse()?,
+        };
+        Passage::with_versification(start, end, versification)
+    }
+
+    pub fn start(&self) -> Reference {
+        self.start
+    }
+
+    pub fn end(&self) -> Reference {
+        self.end
+    }
+
+    /// Each verse of the passage in order.
+    pub fn verses(&self) -> impl Iterator<Item = Reference> + '_ {
+        std::iter::successors(Some(self.start), |verse| {
+            self.versification.next_verse(verse)
+        })
+        .take_while(|verse| *verse <= self.end)
+    }
+
+    pub fn contains(&self, reference: &Reference) -> bool {
+        self.start <= *reference && *reference <= self.end
+    }
+}
+
+impl FromStr for Passage {
+    type Err = ReferenceError;
+
+    /// Parses a passage using the KJV versification. See [`Passage::parse`].
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Passage::parse(text, Versification::default())
+    }
+}
+
+impl fmt::Display for Passage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} - {}", self.start, self.end)
+    }
+}
+
+/// Weeks or verses to leave out of a generated plan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SkipRule {
+    /// Don't start any new verses between these dates, inclusive.
+    Between(NaiveDate, NaiveDate),
+    /// Don't start any new verses every nth week, leaving it for review.
+    /// Ignored for `n < 2`.
+    EveryNthWeek(u32),
+    /// Leave these verses out entirely.
+    Passage(Passage),
+}
+
+impl SkipRule {
+    fn skips_week(&self, week: u32, date: NaiveDate) -> bool {
+        match self {
+            SkipRule::Between(from, to) => *from <= date && date <= *to,
+            SkipRule::EveryNthWeek(n) => *n > 1 && (week + 1).is_multiple_of(*n),
+            SkipRule::Passage(_) => false,
+        }
+    }
+
+    fn skips_verse(&self, reference: &Reference) -> bool {
+        match self {
+            SkipRule::Passage(passage) => passage.contains(reference),
+            _ => false,
+        }
+    }
+}
+
+/// Starts `verses_per_week` verses of a passage every week from `start`,
+/// all on the same weekday as `start`.
+#[derive(Clone, Debug)]
+pub struct PlanGenerator {
+    start: NaiveDate,
+    passage: Passage,
+    verses_per_week: usize,
+    skip: Vec<SkipRule>,
+}
+
+impl PlanGenerator {
+    pub fn new(start: NaiveDate, passage: Passage) -> Self {
+        Self {
+            start,
+            passage,
+            verses_per_week: 1,
+            skip: vec![],
+        }
+    }
+
+    pub fn with_verses_per_week(mut self, verses_per_week: usize) -> Self {
+        self.verses_per_week = verses_per_week.max(1);
+        self
+    }
+
+    pub fn with_skip(mut self, rule: SkipRule) -> Self {
+        self.skip.push(rule);
+        self
+    }
+
+    pub fn entries(&self) -> Vec<VerseEntry> {
+        let mut verses = self
+            .passage
+            .verses()
+            .filter(|verse| !self.skip.iter().any(|rule| rule.skips_verse(verse)))
+            .peekable();
+
+        let mut entries = vec![];
+        let mut week = 0;
+        while verses.peek().is_some() {
+            let date = self.start + Days::new(7 * u64::from(week));
+            if !self.skip.iter().any(|rule| rule.skips_week(week, date)) {
+                entries.extend(
+                    verses
+                        .by_ref()
+                        .take(self.verses_per_week)
+                        .map(|verse| VerseEntry::from_date(date, verse)),
+                );
+            }
+            week += 1;
+        }
+        entries
+    }
+
+    /// The generated entries as a plan whose cycle starts on `start`.
+    pub fn plan(&self) -> Plan {
+        Plan::new(self.entries()).with_start(self.start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(text, crate::FMT).unwrap()
+    }
+
+    fn passage(text: &str) -> Passage {
+        text.parse()
+            .unwrap_or_else(|e| panic!("`{text}` should parse: {e}"))
+    }
+
+    /// Each entry as `date reference`.
+    fn schedule(generator: &PlanGenerator) -> Vec<String> {
+        generator
+            .entries()
+            .iter()
+            .map(|entry| format!("{} {}", entry.date(), entry.reference()))
+            .collect()
+    }
+
+    #[test]
+    fn passages_parse_in_every_form() {
+        let adulteress = Passage::new(
+            Reference::new(Book::John, 7, 53),
+            Reference::new(Book::John, 8, 11),
+        )
+        .unwrap();
+        assert_eq!(passage("John 7:53 - 8:11"), adulteress);
+        assert_eq!(passage("John 7:53-8:11"), adulteress);
+        assert_eq!(passage("John 7:53 – John 8:11"), adulteress);
+        assert_eq!(passage("Jude").to_string(), "Jude 1:1 - Jude 1:25");
+        assert_eq!(passage("Romans 8:28-30").verses().count(), 3);
+        assert_eq!(passage("Malachi 4:5 - Matthew 1:2").verses().count(), 4);
+
+        assert!("John 8:11 - 7:53".parse::<Passage>().is_err());
+        assert!("John 7:53 - 8:99".parse::<Passage>().is_err());
+        assert!("John 7:53 to 8:11".parse::<Passage>().is_err());
+    }
+
+    #[test]
+    fn plans_cross_chapters_and_books() {
+        let generator =
+            PlanGenerator::new(date("2025-07-06"), passage("Malachi 4:5 - Matthew 1:2"));
+        assert_eq!(
+            schedule(&generator),
+            [
+                "2025-07-06 Malachi 4:5",
+                "2025-07-13 Malachi 4:6",
+                "2025-07-20 Matthew 1:1",
+                "2025-07-27 Matthew 1:2",
+            ]
+        );
+        assert_eq!(generator.plan().start(), Some(date("2025-07-06")));
+    }
+
+    #[test]
+    fn several_verses_start_each_week() {
+        let generator = PlanGenerator::new(date("2025-07-06"), passage("John 1:50 - 2:3"))
+            .with_verses_per_week(2);
+        assert_eq!(
+            schedule(&generator),
+            [
+                "2025-07-06 John 1:50",
+                "2025-07-06 John 1:51",
+                "2025-07-13 John 2:1",
+                "2025-07-13 John 2:2",
+                "2025-07-20 John 2:3",
+            ]
+        );
+        // Zero is treated as one.
+        let generator =
+            PlanGenerator::new(date("2025-07-06"), passage("Jude 1:1-2")).with_verses_per_week(0);
+        assert_eq!(generator.entries().len(), 2);
+    }
+
+    #[test]
+    fn skipped_weeks_and_verses_are_left_out() {
+        let start = date("2025-07-06");
+        let between = PlanGenerator::new(start, passage("Jude 1:1-3"))
+            .with_skip(SkipRule::Between(date("2025-07-10"), date("2025-07-20")));
+        assert_eq!(
+            schedule(&between),
+            [
+                "2025-07-06 Jude 1:1",
+                "2025-07-27 Jude 1:2",
+                "2025-08-03 Jude 1:3",
+            ]
+        );
+
+        let every_third =
+            PlanGenerator::new(start, passage("Jude 1:1-4")).with_skip(SkipRule::EveryNthWeek(3));
+        assert_eq!(
+            schedule(&every_third),
+            [
+                "2025-07-06 Jude 1:1",
+                "2025-07-13 Jude 1:2",
+                "2025-07-27 Jude 1:3",
+                "2025-08-03 Jude 1:4",
+            ]
+        );
+        let every_week =
+            PlanGenerator::new(start, passage("Jude 1:1-2")).with_skip(SkipRule::EveryNthWeek(1));
+        assert_eq!(every_week.entries().len(), 2);
+
+        let verses = PlanGenerator::new(start, passage("Jude 1:1-5"))
+            .with_skip(SkipRule::Passage(passage("Jude 1:2-3")));
+        assert_eq!(
+            schedule(&verses),
+            [
+                "2025-07-06 Jude 1:1",
+                "2025-07-13 Jude 1:4",
+                "2025-07-20 Jude 1:5",
+            ]
+        );
+    }
+}
@@ -0,0 +1,1697 @@
+#![allow(unused)]
+
+pub mod adherence;
+pub mod booklet;
+pub mod calendar;
+mod error;
+pub mod forecast;
+pub mod generate;
+pub mod parser;
+mod plan;
+pub mod reference;
+pub mod review;
+pub mod srs;
+pub mod versification;
+
+pub use error::{Error, Result};
+pub use parser::{Span, parse_entries, parse_entries_strict, parse_plan, parse_plan_strict};
+pub use plan::Plan;
+pub use reference::{Book, Reference};
+
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use itertools::Itertools;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::review::{Adjustment, Grade, ReviewLog};
+
+/// Splits `vec` into `n` contiguous parts of roughly equal total weight. Each
+/// part takes items until it would go over its share of what's left, so with
+/// unit weights the parts differ by at most one and the earlier ones get the
+/// remainder.
+fn split_by_weight<T: Clone>(vec: Vec<T>, n: usize, weight: impl Fn(&T) -> usize) -> Vec<Vec<T>> {
+    let mut remaining: usize = vec.iter().map(&weight).sum();
+    let mut items = vec.into_iter().peekable();
+
+    let mut result = Vec::with_capacity(n);
+    for i in 0..n {
+        let parts_left = n - i;
+        let target = remaining.div_ceil(parts_left);
+        let mut part = vec![];
+        let mut total = 0;
+        while let Some(item) = items.peek() {
+            let w = weight(item);
+            let is_last = parts_left == 1;
+            if !is_last && !part.is_empty() && total + w > target {
+                break;
+            }
+            total += w;
+            part.push(items.next().unwrap());
+        }
+        remaining -= total;
+        result.push(part);
+    }
+
+    result
+}
+
+#[derive(Debug)]
+pub struct VerseEntry {
+    date: NaiveDate,
+    reference: Reference,
+    pauses: Vec<Pause>,
+    span: Option<Span>,
+}
+
+pub const FMT: &str = "%Y-%m-%d";
+
+impl VerseEntry {
+    pub fn new(date: &str, reference: &str) -> Result<Self> {
+        let date = NaiveDate::parse_from_str(date, FMT)?;
+        Ok(Self::from_date(date, reference.parse()?))
+    }
+
+    pub fn from_date(date: NaiveDate, reference: Reference) -> Self {
+        Self {
+            date,
+            reference,
+            pauses: vec![],
+            span: None,
+        }
+    }
+
+    /// Stops this verse's schedule during `pause`.
+    pub fn with_pause(mut self, pause: Pause) -> Self {
+        self.pauses.push(pause);
+        self
+    }
+
+    /// Records where in a verse list this entry was read from.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    pub fn reference(&self) -> &Reference {
+        &self.reference
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    /// Pauses that apply to just this verse.
+    pub fn pauses(&self) -> &[Pause] {
+        &self.pauses
+    }
+
+    /// Days since this verse was started, negative if it hasn't been yet,
+    /// leaving out days in any of its pauses.
+    pub fn days_in(&self, today: NaiveDate) -> i64 {
+        self.active_days_in(today, &[])
+    }
+
+    /// Like [`VerseEntry::days_in`], but also leaving out days in `pauses`,
+    /// such as those that apply to the whole plan.
+    pub fn active_days_in(&self, today: NaiveDate, pauses: &[Pause]) -> i64 {
+        let days = (today - self.date).num_days();
+        days - Pause::days_between(self.pauses.iter().chain(pauses), self.date, today)
+    }
+
+    /// Whether this verse is paused on `date`, by its own pauses or `pauses`.
+    pub fn is_paused(&self, date: NaiveDate, pauses: &[Pause]) -> bool {
+        self.pauses
+            .iter()
+            .chain(pauses)
+            .any(|pause| pause.contains(date))
+    }
+
+    /// Whole weeks since this verse was started, rounding down, so a verse
+    /// starting later this week is -1 weeks in rather than 0.
+    pub fn weeks_in(&self, today: NaiveDate) -> i64 {
+        self.days_in(today).div_euclid(7)
+    }
+
+    pub fn frequency(&self, today: NaiveDate, config: &ScheduleConfig) -> Frequency {
+        Frequency::new(self.weeks_in(today), config)
+    }
+
+    pub fn calculate_relative(&self, today: NaiveDate) -> Verse<'_> {
+        self.calculate_paused(today, &[])
+    }
+
+    /// Like [`VerseEntry::calculate_relative`], but counting only days
+    /// outside `pauses` as well as its own.
+    pub fn calculate_paused(&self, today: NaiveDate, pauses: &[Pause]) -> Verse<'_> {
+        Verse {
+            days_in: self.active_days_in(today, pauses),
+            reference: Cow::Borrowed(&self.reference),
+        }
+    }
+}
+
+impl fmt::Display for VerseEntry {
+    /// Formats the entry as a line of a verse list.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} | {}", self.date.format(FMT), self.reference)?;
+        if !self.pauses.is_empty() {
+            write!(f, " | {}", self.pauses.iter().join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A break in reviewing, from `from` to `to` inclusive. Days in a pause don't
+/// count towards a verse's time in its phase, so everything after it is
+/// pushed back by its length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Pause {
+    from: NaiveDate,
+    to: NaiveDate,
+}
+
+impl Pause {
+    /// The days from `from` to `to`, in either order.
+    pub fn new(from: NaiveDate, to: NaiveDate) -> Self {
+        Self {
+            from: from.min(to),
+            to: from.max(to),
+        }
+    }
+
+    pub fn from(&self) -> NaiveDate {
+        self.from
+    }
+
+    pub fn to(&self) -> NaiveDate {
+        self.to
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.from <= date && date <= self.to
+    }
+
+    /// How many days from `start` up to but not including `end` fall in any
+    /// of `pauses`, counting days covered by more than one pause once.
+    pub fn days_between<'p>(
+        pauses: impl IntoIterator<Item = &'p Pause>,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> i64 {
+        let Some(last) = end.pred_opt() else {
+            return 0;
+        };
+        let clipped = pauses
+            .into_iter()
+            .map(|pause| (pause.from.max(start), pause.to.min(last)))
+            .sorted();
+        let mut covered = 0;
+        // The first day that hasn't been counted yet.
+        let mut next = start;
+        for (from, to) in clipped {
+            let from = from.max(next);
+            if from <= to {
+                covered += (to - from).num_days() + 1;
+                next = to + Days::new(1);
+            }
+        }
+        covered
+    }
+}
+
+impl FromStr for Pause {
+    type Err = String;
+
+    /// Parses `YYYY-MM-DD..YYYY-MM-DD`.
+    fn from_str(text: &str) -> std::result::Result<Self, Self::Err> {
+        let expected = || "expected YYYY-MM-DD..YYYY-MM-DD".to_string();
+        let (from, to) = text.split_once("..").ok_or_else(expected)?;
+        let date = |date: &str| NaiveDate::parse_from_str(date.trim(), FMT).map_err(|_| expected());
+        Ok(Pause::new(date(from)?, date(to)?))
+    }
+}
+
+impl fmt::Display for Pause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.from.format(FMT), self.to.format(FMT))
+    }
+}
+
+#[derive(Debug)]
+pub struct VerseList {
+    today: NaiveDate,
+    references: Vec<VerseEntry>,
+}
+
+impl VerseList {
+    pub fn new(date: &str, references: Vec<VerseEntry>) -> Result<Self> {
+        let today = NaiveDate::parse_from_str(date, FMT)?;
+        Ok(Self::from_date(today, references))
+    }
+
+    pub fn from_date(today: NaiveDate, references: Vec<VerseEntry>) -> Self {
+        Self { today, references }
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.today
+    }
+
+    pub fn references(&self) -> &[VerseEntry] {
+        &self.references
+    }
+
+    pub fn relative_verses(&self) -> Vec<Verse<'_>> {
+        self.references
+            .iter()
+            .map(|verse| verse.calculate_relative(self.today))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Verse<'a> {
+    days_in: i64,
+    reference: Cow<'a, Reference>,
+}
+
+impl<'a> Verse<'a> {
+    pub fn days_in(&self) -> i64 {
+        self.days_in
+    }
+
+    /// Whole weeks in, rounding down. Since the phase lengths are whole
+    /// weeks, phases still change on the exact day.
+    pub fn weeks_in(&self) -> i64 {
+        self.days_in.div_euclid(7)
+    }
+
+    pub fn reference(&self) -> &Reference {
+        &self.reference
+    }
+
+    /// How much review time this verse takes, i.e. how many verses it spans.
+    pub fn weight(&self) -> usize {
+        self.reference.verse_count() as usize
+    }
+
+    pub fn frequency(&self, config: &ScheduleConfig) -> Frequency {
+        Frequency::new(self.weeks_in(), config)
+    }
+
+    pub fn add_offset(&mut self, weeks: i64) {
+        self.days_in += 7 * weeks;
+    }
+
+    pub fn with_offset(&self, weeks: i64) -> Self {
+        self.with_day_offset(7 * weeks)
+    }
+
+    /// This verse as of `days` days later.
+    pub fn with_day_offset(&self, days: i64) -> Self {
+        let mut it = self.clone();
+        it.days_in += days;
+        it
+    }
+
+    pub fn is_daily(&self, config: &ScheduleConfig) -> bool {
+        self.frequency(config) == Frequency::Daily
+    }
+
+    pub fn is_weekly(&self, config: &ScheduleConfig) -> bool {
+        self.frequency(config) == Frequency::Weekly
+    }
+
+    pub fn is_monthly(&self, config: &ScheduleConfig) -> bool {
+        self.frequency(config) == Frequency::Monthly
+    }
+
+    pub fn will_be_monthly_this_month(&self, n: i64, config: &ScheduleConfig) -> bool {
+        self.is_monthly(config) || self.with_offset(n).is_monthly(config)
+    }
+
+    pub fn is_yearly(&self, config: &ScheduleConfig) -> bool {
+        self.frequency(config) == Frequency::Yearly
+    }
+
+    /// Yearly verses are reviewed in the week that marks a whole number of
+    /// yearly cycles since they finished their monthly phase.
+    pub fn is_yearly_week(&self, config: &ScheduleConfig) -> bool {
+        let Some(cycle) = config.yearly_weeks.filter(|cycle| *cycle > 0) else {
+            return false;
+        };
+        self.is_yearly(config) && (self.weeks_in() - config.monthly_end()) % cycle == 0
+    }
+
+    pub fn is_monthly_week(&self, n: i64, config: &ScheduleConfig) -> bool {
+        let is_monthly = self.frequency(config) == Frequency::Monthly;
+        let is_monthly_this_week = self.weeks_in() % 4 == n;
+        is_monthly && is_monthly_this_week
+    }
+}
+
+pub struct RelativeVerseList {}
+
+/// How many weeks a verse spends in each phase before moving on to the next.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScheduleConfig {
+    pub daily_weeks: i64,
+    pub weekly_weeks: i64,
+    pub monthly_weeks: i64,
+    /// Length of the optional post-monthly review cycle. Verses that finish
+    /// their monthly phase are reviewed once per cycle instead of being dropped.
+    pub yearly_weeks: Option<i64>,
+    /// What a "month" is when spreading out monthly verses.
+    pub month_mode: MonthMode,
+    /// The first day of each week, i.e. day 0 of a [`VersesForAWeek`].
+    pub week_start: Weekday,
+}
+
+impl ScheduleConfig {
+    /// 7 weeks daily, 28 weeks weekly, then 336 weeks (~6.5 years) monthly.
+    pub const fn frost() -> Self {
+        Self {
+            daily_weeks: 7,
+            weekly_weeks: 28,
+            monthly_weeks: 336,
+            yearly_weeks: None,
+            month_mode: MonthMode::FourWeek,
+            week_start: Weekday::Sun,
+        }
+    }
+
+    /// Which day of its week `date` is, counting from [`Self::week_start`].
+    pub fn day_of_week(&self, date: NaiveDate) -> usize {
+        date.weekday().days_since(self.week_start) as usize
+    }
+
+    /// The first day of the week containing `date`.
+    pub fn start_of_week(&self, date: NaiveDate) -> NaiveDate {
+        date - Days::new(self.day_of_week(date) as u64)
+    }
+
+    pub fn monthly_end(&self) -> i64 {
+        self.daily_weeks + self.weekly_weeks + self.monthly_weeks
+    }
+
+    /// The first day of the month containing `date`: the calendar month, or
+    /// the 4-week cycle counted from the week of `anchor`.
+    pub fn start_of_month(&self, anchor: NaiveDate, date: NaiveDate) -> NaiveDate {
+        match self.month_mode {
+            MonthMode::FourWeek => {
+                let start = self.start_of_week(anchor);
+                let days = (self.start_of_week(date) - start).num_days();
+                start + chrono::Duration::days(days - days.rem_euclid(28))
+            }
+            MonthMode::Calendar => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// Days between reviews of a verse in `phase`, taking a month as four
+    /// weeks. `None` for verses that aren't being reviewed.
+    pub fn interval(&self, phase: Frequency) -> Option<i64> {
+        match phase {
+            Frequency::Daily => Some(1),
+            Frequency::Weekly => Some(7),
+            Frequency::Monthly => Some(28),
+            Frequency::Yearly => self
+                .yearly_weeks
+                .filter(|weeks| *weeks > 0)
+                .map(|weeks| 7 * weeks),
+            Frequency::NotStarted | Frequency::Done => None,
+        }
+    }
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self::frost()
+    }
+}
+
+/// How monthly verses are spread out.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MonthMode {
+    /// Over a 4-week cycle counted from the start of the plan, ignoring the
+    /// calendar. See [`VersesForAMonth`].
+    #[default]
+    FourWeek,
+    /// Over the days of the Gregorian month, as in Tom Frost's original
+    /// system. See [`VersesForACalendarMonth`].
+    Calendar,
+}
+
+impl FromStr for MonthMode {
+    type Err = String;
+
+    fn from_str(name: &str) -> std::result::Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "four-week" | "4-week" => Ok(MonthMode::FourWeek),
+            "calendar" | "gregorian" => Ok(MonthMode::Calendar),
+            _ => Err(format!(
+                "unknown month mode `{name}`, expected four-week or calendar"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for MonthMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MonthMode::FourWeek => f.write_str("four-week"),
+            MonthMode::Calendar => f.write_str("calendar"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Frequency {
+    NotStarted,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+    Done,
+}
+
+impl Frequency {
+    pub fn new(weeks_in: i64, config: &ScheduleConfig) -> Self {
+        let daily_end = config.daily_weeks;
+        let weekly_end = daily_end + config.weekly_weeks;
+        let monthly_end = config.monthly_end();
+        if weeks_in < 0 {
+            Frequency::NotStarted
+        } else if weeks_in < daily_end {
+            Frequency::Daily
+        } else if weeks_in < weekly_end {
+            Frequency::Weekly
+        } else if weeks_in < monthly_end {
+            Frequency::Monthly
+        } else if config.yearly_weeks.is_some() {
+            Frequency::Yearly
+        } else {
+            Frequency::Done
+        }
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Frequency::NotStarted => "not_started",
+            Frequency::Daily => "daily",
+            Frequency::Weekly => "weekly",
+            Frequency::Monthly => "monthly",
+            Frequency::Yearly => "yearly",
+            Frequency::Done => "done",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct VersesForADay<'a> {
+    date: Option<NaiveDate>,
+    daily: Vec<Verse<'a>>,
+    weekly: Vec<Verse<'a>>,
+    monthly: Vec<Verse<'a>>,
+    yearly: Vec<Verse<'a>>,
+    carried_over: Vec<CarriedOver<'a>>,
+}
+
+impl<'a> VersesForADay<'a> {
+    /// The day these verses are assigned to, if the schedule knows its dates.
+    pub fn date(&self) -> Option<NaiveDate> {
+        self.date
+    }
+
+    pub fn daily(&self) -> &[Verse<'a>] {
+        &self.daily
+    }
+
+    pub fn weekly(&self) -> &[Verse<'a>] {
+        &self.weekly
+    }
+
+    pub fn monthly(&self) -> &[Verse<'a>] {
+        &self.monthly
+    }
+
+    pub fn yearly(&self) -> &[Verse<'a>] {
+        &self.yearly
+    }
+
+    /// Reviews from recently missed days, when catching up. See
+    /// [`ScheduledVerses::with_catch_up`].
+    pub fn carried_over(&self) -> &[CarriedOver<'a>] {
+        &self.carried_over
+    }
+
+    /// Every verse to review on this day, carried-over ones included, with
+    /// the phase it's being reviewed in.
+    pub fn items(&self) -> impl Iterator<Item = (Frequency, &Verse<'a>)> {
+        let scheduled = [
+            (Frequency::Daily, &self.daily),
+            (Frequency::Weekly, &self.weekly),
+            (Frequency::Monthly, &self.monthly),
+            (Frequency::Yearly, &self.yearly),
+        ]
+        .into_iter()
+        .flat_map(|(phase, verses)| verses.iter().map(move |verse| (phase, verse)));
+        let carried_over = self.carried_over.iter().map(|c| (c.phase, &c.verse));
+        scheduled.chain(carried_over)
+    }
+
+    pub fn data(&self) -> String {
+        let daily = self.daily.iter().map(|v| &v.reference).join("\n- ");
+        let weekly = self.weekly.iter().map(|v| &v.reference).join("\n- ");
+        let monthly = self.monthly.iter().map(|v| &v.reference).join("\n- ");
+        let mut sections = vec![
+            format!("### Daily: \n- {}", daily),
+            format!("### Weekly: \n- {}", weekly),
+            format!("### Monthly: \n- {}", monthly),
+        ];
+        if !self.yearly.is_empty() {
+            let yearly = self.yearly.iter().map(|v| &v.reference).join("\n- ");
+            sections.push(format!("### Yearly: \n- {}", yearly));
+        }
+        if !self.carried_over.is_empty() {
+            let carried_over = self
+                .carried_over
+                .iter()
+                .map(|c| format!("{} (from {})", c.verse.reference, c.missed))
+                .join("\n- ");
+            sections.push(format!("### Carried over: \n- {}", carried_over));
+        }
+        sections.join("\n\n")
+    }
+}
+
+/// A weekly, monthly or yearly review from a missed day, moved to a later one.
+#[derive(Clone, Debug)]
+pub struct CarriedOver<'a> {
+    verse: Verse<'a>,
+    phase: Frequency,
+    missed: NaiveDate,
+}
+
+impl<'a> CarriedOver<'a> {
+    pub fn verse(&self) -> &Verse<'a> {
+        &self.verse
+    }
+
+    /// Which list the verse was in on the day it was missed.
+    pub fn phase(&self) -> Frequency {
+        self.phase
+    }
+
+    /// The day it was originally assigned to.
+    pub fn missed(&self) -> NaiveDate {
+        self.missed
+    }
+}
+
+/// Which days were reviewed, so that reviews from the days that weren't can be
+/// spread over the next `days` days.
+#[derive(Clone, Debug, Default)]
+pub struct CatchUp {
+    completed: BTreeSet<NaiveDate>,
+    days: usize,
+}
+
+impl CatchUp {
+    pub fn new(completed: BTreeSet<NaiveDate>, days: usize) -> Self {
+        Self { completed, days }
+    }
+
+    /// Days before the first completed one aren't counted as missed, so a new
+    /// log doesn't carry over the whole plan's history.
+    pub fn is_missed(&self, date: NaiveDate) -> bool {
+        self.completed
+            .first()
+            .is_some_and(|first| *first <= date && !self.completed.contains(&date))
+    }
+}
+
+impl Serialize for VersesForADay<'_> {
+    /// Each verse is written with its phase and the date it's assigned to, so
+    /// consumers don't need to know which list it came from.
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Assigned<'b> {
+            reference: &'b Reference,
+            weeks_in: i64,
+            phase: Frequency,
+            date: Option<NaiveDate>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            missed: Option<NaiveDate>,
+        }
+
+        fn assigned<'b>(
+            verses: &'b [Verse],
+            phase: Frequency,
+            date: Option<NaiveDate>,
+        ) -> Vec<Assigned<'b>> {
+            verses
+                .iter()
+                .map(|verse| Assigned {
+                    reference: &verse.reference,
+                    weeks_in: verse.weeks_in(),
+                    phase,
+                    date,
+                    missed: None,
+                })
+                .collect()
+        }
+
+        let carried_over = self
+            .carried_over
+            .iter()
+            .map(|c| Assigned {
+                reference: &c.verse.reference,
+                weeks_in: c.verse.weeks_in(),
+                phase: c.phase,
+                date: self.date,
+                missed: Some(c.missed),
+            })
+            .collect_vec();
+
+        let mut day = serializer.serialize_struct("VersesForADay", 6)?;
+        day.serialize_field("date", &self.date)?;
+        day.serialize_field("daily", &assigned(&self.daily, Frequency::Daily, self.date))?;
+        day.serialize_field(
+            "weekly",
+            &assigned(&self.weekly, Frequency::Weekly, self.date),
+        )?;
+        day.serialize_field(
+            "monthly",
+            &assigned(&self.monthly, Frequency::Monthly, self.date),
+        )?;
+        day.serialize_field(
+            "yearly",
+            &assigned(&self.yearly, Frequency::Yearly, self.date),
+        )?;
+        day.serialize_field("carried_over", &carried_over)?;
+        day.end()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct VersesForAWeek<'a> {
+    days: Vec<VersesForADay<'a>>,
+}
+
+impl<'a> VersesForAWeek<'a> {
+    pub fn days(&self) -> &[VersesForADay<'a>] {
+        &self.days
+    }
+
+    /// Week `n` of a 4-week cycle, where `verses` are relative to the first
+    /// day of the cycle. Each day's verses are relative to that day.
+    pub fn new(verses: &[Verse<'a>], n: i64, config: &ScheduleConfig) -> Self {
+        let week: Vec<_> = verses.iter().map(|verse| verse.with_offset(n)).collect();
+
+        let weekly: Vec<_> = week
+            .iter()
+            .filter(|verse| verse.is_weekly(config))
+            .cloned()
+            .collect();
+
+        // Every verse that is monthly at some point this cycle, split into 4
+        // bins so each one is reviewed exactly once per 4-week cycle.
+        let monthly: Vec<_> = verses
+            .iter()
+            .filter(|verse| {
+                verse.is_monthly(config) || verse.with_day_offset(27).is_monthly(config)
+            })
+            .map(|verse| verse.with_offset(n))
+            .collect_vec();
+        let monthly = split_by_weight(monthly, 4, Verse::weight).swap_remove(n as usize);
+
+        let yearly: Vec<_> = week
+            .iter()
+            .filter(|verse| verse.is_yearly_week(config))
+            .cloned()
+            .collect();
+
+        let weekly = split_by_weight(weekly, 7, Verse::weight);
+        let monthly = split_by_weight(monthly, 7, Verse::weight);
+        let yearly = split_by_weight(yearly, 7, Verse::weight);
+        let days = weekly
+            .into_iter()
+            .zip(monthly)
+            .zip(yearly)
+            .enumerate()
+            .map(|(d, ((weekly, monthly), yearly))| {
+                let on_day = |verses: Vec<Verse<'a>>| {
+                    verses
+                        .iter()
+                        .map(|verse| verse.with_day_offset(d as i64))
+                        .collect_vec()
+                };
+                let daily = week
+                    .iter()
+                    .map(|verse| verse.with_day_offset(d as i64))
+                    .filter(|verse| verse.is_daily(config))
+                    .collect();
+                VersesForADay {
+                    date: None,
+                    daily,
+                    weekly: on_day(weekly),
+                    monthly: on_day(monthly),
+                    yearly: on_day(yearly),
+                    carried_over: vec![],
+                }
+            })
+            .collect_vec();
+        Self { days }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct VersesForAMonth<'a> {
+    weeks: Vec<VersesForAWeek<'a>>,
+}
+
+impl<'a> VersesForAMonth<'a> {
+    /// The 4 weeks of a cycle, where `verses` are relative to its first day.
+    pub fn new(verses: &[Verse<'a>], config: &ScheduleConfig) -> Self {
+        let weeks = (0..=3)
+            .map(|n| VersesForAWeek::new(verses, n, config))
+            .collect_vec();
+        Self { weeks }
+    }
+
+    /// The 4 weeks from `start`, with each day's verses from `for_day`, such
+    /// as a [`Scheduler`] made for that day.
+    pub fn from_days(start: NaiveDate, for_day: impl Fn(NaiveDate) -> VersesForADay<'a>) -> Self {
+        let mut days = start.iter_days().map(|date| VersesForADay {
+            date: Some(date),
+            ..for_day(date)
+        });
+        let weeks = (0..4)
+            .map(|_| VersesForAWeek {
+                days: days.by_ref().take(7).collect(),
+            })
+            .collect();
+        Self { weeks }
+    }
+
+    pub fn weeks(&self) -> &[VersesForAWeek<'a>] {
+        &self.weeks
+    }
+
+    /// Dates every day of the month, counting from `start` as the first day
+    /// of the first week.
+    pub fn with_start_date(mut self, start: NaiveDate) -> Self {
+        let days = self.weeks.iter_mut().flat_map(|week| &mut week.days);
+        for (day, date) in days.zip(start.iter_days()) {
+            day.date = Some(date);
+        }
+        self
+    }
+
+    pub fn stats(&self) -> String {
+        self.weeks
+            .iter()
+            .map(|week| {
+                week.days
+                    .iter()
+                    .map(|day| {
+                        format!(
+                            "D: {} | W: {} | M: {} | Y: {}\n{}",
+                            // "D: {} | W: {} | M: {}",
+                            day.daily.len(),
+                            day.weekly.len(),
+                            day.monthly.len(),
+                            day.yearly.len(),
+                            day.monthly.iter().map(|v| &v.reference).join(" + "),
+                        )
+                    })
+                    .join("\n")
+            })
+            .join("\n---\n")
+    }
+}
+
+/// Every day of the Gregorian month containing a date, for
+/// [`MonthMode::Calendar`]. Monthly verses are split across the 28-31 days of
+/// the month so each is reviewed once per calendar month.
+#[derive(Debug, Serialize)]
+pub struct VersesForACalendarMonth<'a> {
+    days: Vec<VersesForADay<'a>>,
+    #[serde(skip)]
+    week_start: Weekday,
+}
+
+impl<'a> VersesForACalendarMonth<'a> {
+    /// `verses` are relative to `today`.
+    pub fn new(verses: &[Verse<'a>], today: NaiveDate, config: &ScheduleConfig) -> Self {
+        let first = today.with_day(1).expect("every month has a first day");
+        let dates = first
+            .iter_days()
+            .take_while(|date| date.month() == first.month())
+            .collect_vec();
+        let last = *dates.last().expect("every month has a day");
+        let days_from_today = |date: NaiveDate| (date - today).num_days();
+
+        // Every verse that is monthly at some point this month, one bin per day.
+        let monthly = verses
+            .iter()
+            .filter(|verse| {
+                verse
+                    .with_day_offset(days_from_today(first))
+                    .is_monthly(config)
+                    || verse
+                        .with_day_offset(days_from_today(last))
+                        .is_monthly(config)
+            })
+            .cloned()
+            .collect_vec();
+        let monthly = split_by_weight(monthly, dates.len(), Verse::weight);
+
+        let days = dates
+            .into_iter()
+            .zip(monthly)
+            .map(|(date, monthly)| {
+                // Weekly and yearly verses follow their own weeks, which may
+                // straddle the start or end of the month.
+                let week_begins = days_from_today(config.start_of_week(date));
+                let day = config.day_of_week(date);
+                let on_day = |verses: Vec<Verse<'a>>| {
+                    let offset = days_from_today(date);
+                    verses
+                        .iter()
+                        .map(|v| v.with_day_offset(offset))
+                        .collect_vec()
+                };
+                let this_week = |keep: &dyn Fn(&Verse) -> bool| {
+                    let verses = verses
+                        .iter()
+                        .filter(|v| keep(&v.with_day_offset(week_begins)))
+                        .cloned()
+                        .collect_vec();
+                    on_day(split_by_weight(verses, 7, Verse::weight).swap_remove(day))
+                };
+                let daily = verses
+                    .iter()
+                    .filter(|v| v.with_day_offset(days_from_today(date)).is_daily(config))
+                    .cloned()
+                    .collect();
+                VersesForADay {
+                    date: Some(date),
+                    daily: on_day(daily),
+                    weekly: this_week(&|v| v.is_weekly(config)),
+                    monthly: on_day(monthly),
+                    yearly: this_week(&|v| v.is_yearly_week(config)),
+                    carried_over: vec![],
+                }
+            })
+            .collect_vec();
+        Self {
+            days,
+            week_start: config.week_start,
+        }
+    }
+
+    /// Every day of the Gregorian month containing `date`, with each day's
+    /// verses from `for_day`, such as a [`Scheduler`] made for that day.
+    pub fn from_days(
+        date: NaiveDate,
+        config: &ScheduleConfig,
+        for_day: impl Fn(NaiveDate) -> VersesForADay<'a>,
+    ) -> Self {
+        let first = date.with_day(1).expect("every month has a first day");
+        let days = first
+            .iter_days()
+            .take_while(|day| day.month() == first.month())
+            .map(|day| VersesForADay {
+                date: Some(day),
+                ..for_day(day)
+            })
+            .collect();
+        Self {
+            days,
+            week_start: config.week_start,
+        }
+    }
+
+    pub fn days(&self) -> &[VersesForADay<'a>] {
+        &self.days
+    }
+
+    /// The days split into weeks starting on the configured week start, so
+    /// the first and last may be shorter than 7 days.
+    pub fn weeks(&self) -> impl Iterator<Item = &[VersesForADay<'a>]> {
+        self.days.chunk_by(|_, day| {
+            let date = day.date.expect("calendar days are dated");
+            date.weekday() != self.week_start
+        })
+    }
+
+    /// Daily/weekly/monthly counts for each day, in the same format as
+    /// [`VersesForAMonth::stats`], with a break between weeks.
+    pub fn stats(&self) -> String {
+        self.days
+            .iter()
+            .map(|day| {
+                let date = day.date.expect("calendar days are dated");
+                let separator = if date.day() > 1 && date.weekday() == self.week_start {
+                    "---\n"
+                } else {
+                    ""
+                };
+                format!(
+                    "{separator}D: {} | W: {} | M: {} | Y: {}\n{}",
+                    day.daily.len(),
+                    day.weekly.len(),
+                    day.monthly.len(),
+                    day.yearly.len(),
+                    day.monthly.iter().map(|v| &v.reference).join(" + "),
+                )
+            })
+            .join("\n")
+    }
+}
+
+/// A way of picking the verses to review on a day.
+pub trait Scheduler<'a> {
+    /// The day being scheduled.
+    fn date(&self) -> NaiveDate;
+
+    /// The verses to review on [`Scheduler::date`].
+    fn for_today(&self) -> VersesForADay<'a>;
+
+    /// Every verse still being reviewed as of [`Scheduler::date`], whether
+    /// or not it's due that day, with its phase and the days between its
+    /// reviews.
+    fn intervals(&self) -> Vec<(Frequency, i64, Verse<'a>)>;
+}
+
+#[derive(Debug)]
+pub struct ScheduledVerses<'a> {
+    date: NaiveDate,
+    anchor: NaiveDate,
+    verses: Vec<Verse<'a>>,
+    /// The entry each of `verses` came from, for rebuilding it on other days.
+    entries: Vec<&'a VerseEntry>,
+    /// Pauses that apply to every verse.
+    pauses: Vec<Pause>,
+    config: ScheduleConfig,
+    catch_up: Option<CatchUp>,
+    /// The grades each of `verses` was given, from
+    /// [`ScheduledVerses::with_grades`], applied again whenever the config
+    /// changes.
+    grades: Option<Vec<Vec<(Frequency, Grade)>>>,
+}
+impl<'a> ScheduledVerses<'a> {
+    pub fn new(date: &str, verses: impl IntoIterator<Item = &'a VerseEntry> + 'a) -> Result<Self> {
+        let date = NaiveDate::parse_from_str(date, FMT)?;
+        Ok(Self::from_date(date, verses))
+    }
+
+    pub fn from_date(
+        date: NaiveDate,
+        verses: impl IntoIterator<Item = &'a VerseEntry> + 'a,
+    ) -> Self {
+        Self::from_date_with_pauses(date, verses, &[])
+    }
+
+    /// Like [`ScheduledVerses::from_date`], but with `pauses` applying to
+    /// every verse on top of their own. Verses that are paused on `date`
+    /// aren't scheduled at all.
+    pub fn from_date_with_pauses(
+        date: NaiveDate,
+        verses: impl IntoIterator<Item = &'a VerseEntry> + 'a,
+        pauses: &[Pause],
+    ) -> Self {
+        // Sorted so the schedule doesn't depend on the order of the list.
+        let entries = verses
+            .into_iter()
+            .sorted_by_key(|entry| (entry.date, entry.reference))
+            .collect_vec();
+        let anchor = entries.first().map_or(date, |entry| entry.date);
+        let entries = entries
+            .into_iter()
+            .filter(|verse| !verse.is_paused(date, pauses))
+            .collect_vec();
+        let verses = entries
+            .iter()
+            .map(|verse| verse.calculate_paused(date, pauses))
+            .collect();
+        Self {
+            date,
+            anchor,
+            verses,
+            entries,
+            pauses: pauses.to_vec(),
+            config: ScheduleConfig::default(),
+            catch_up: None,
+            grades: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: ScheduleConfig) -> Self {
+        self.config = config;
+        self.apply_grades();
+        self
+    }
+
+    /// Moves the weekly, monthly and yearly reviews of days missing from
+    /// `catch_up`'s log into [`VersesForADay::carried_over`] over the
+    /// following days.
+    pub fn with_catch_up(mut self, catch_up: CatchUp) -> Self {
+        self.catch_up = Some(catch_up);
+        self
+    }
+
+    /// Moves each verse through its daily and weekly phases faster or slower
+    /// according to the grades it was given in `log` before the scheduled
+    /// date (see [`Adjustment::of`]). How far a grade can move a verse
+    /// depends on the phase lengths in the config, whether it's set before
+    /// or after this.
+    pub fn with_grades(mut self, log: &ReviewLog) -> Self {
+        let grades = self
+            .verses
+            .iter()
+            .map(|verse| log.grades(&verse.reference, self.date))
+            .collect();
+        self.grades = Some(grades);
+        self.apply_grades();
+        self
+    }
+
+    /// Sets each verse's days in from its entry, moved by its grades under
+    /// the current config.
+    fn apply_grades(&mut self) {
+        let Some(grades) = &self.grades else {
+            return;
+        };
+        for ((verse, entry), grades) in self.verses.iter_mut().zip(&self.entries).zip(grades) {
+            let days_in = entry.active_days_in(self.date, &self.pauses);
+            verse.days_in = Adjustment::of(grades, &self.config).apply(days_in, &self.config);
+        }
+    }
+
+    /// Counts the 4-week cycle from `anchor` instead of the earliest verse.
+    pub fn with_anchor(mut self, anchor: NaiveDate) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    /// The date the 4-week cycle counts from.
+    pub fn anchor(&self) -> NaiveDate {
+        self.anchor
+    }
+
+    pub fn config(&self) -> &ScheduleConfig {
+        &self.config
+    }
+
+    pub fn verses(&self) -> &[Verse<'a>] {
+        &self.verses
+    }
+
+    pub fn monthly_schedule(&self) -> VersesForAMonth<'a> {
+        let days_in = (self.current_week_offset() * 7 + self.config.day_of_week(self.date)) as i64;
+        let verses = self
+            .verses
+            .iter()
+            .map(|verse| verse.with_day_offset(-days_in))
+            .collect_vec();
+        VersesForAMonth::new(&verses, &self.config)
+            .with_start_date(self.date - Days::new(days_in as u64))
+    }
+
+    /// Every day of the Gregorian month containing this date, regardless of
+    /// [`ScheduleConfig::month_mode`].
+    pub fn calendar_month_schedule(&self) -> VersesForACalendarMonth<'a> {
+        VersesForACalendarMonth::new(&self.verses, self.date, &self.config)
+    }
+
+    /// Which week of the 4-week cycle this date falls in, counting whole
+    /// weeks since the week containing the anchor.
+    pub fn current_week_offset(&self) -> usize {
+        let start = self.config.start_of_week(self.anchor);
+        let weeks = (self.config.start_of_week(self.date) - start).num_days() / 7;
+        weeks.rem_euclid(4) as usize
+    }
+
+    pub fn for_today(&self) -> VersesForADay<'a> {
+        let mut day = self.scheduled_today();
+        if let Some(catch_up) = &self.catch_up {
+            day.carried_over = self.carried_over(catch_up, &day);
+        }
+        day
+    }
+
+    fn scheduled_today(&self) -> VersesForADay<'a> {
+        if self.config.month_mode == MonthMode::Calendar {
+            let mut days = self.calendar_month_schedule().days;
+            return days.swap_remove(self.date.day0() as usize);
+        }
+        let week = self.current_week_offset();
+        let m = self.monthly_schedule();
+        let week = m.weeks.get(week);
+
+        week.and_then(|week| week.days.get(self.config.day_of_week(self.date)).cloned())
+            .unwrap_or_default()
+    }
+
+    /// The schedule as it was on an earlier (or later) `date`. Verses paused
+    /// on `date` are left out, and each verse is moved by the days between
+    /// the two dates that it wasn't paused for, keeping any adjustment from
+    /// [`ScheduledVerses::with_grades`].
+    fn on(&self, date: NaiveDate) -> Self {
+        let (entries, verses) = self
+            .entries
+            .iter()
+            .zip(&self.verses)
+            .filter(|(entry, _)| !entry.is_paused(date, &self.pauses))
+            .map(|(entry, verse)| {
+                let offset = entry.active_days_in(date, &self.pauses)
+                    - entry.active_days_in(self.date, &self.pauses);
+                (*entry, verse.with_day_offset(offset))
+            })
+            .unzip();
+        Self {
+            date,
+            anchor: self.anchor,
+            verses,
+            entries,
+            pauses: self.pauses.clone(),
+            config: self.config,
+            catch_up: None,
+            // Already applied to `verses`.
+            grades: None,
+        }
+    }
+
+    /// Each missed day's reviews are split evenly over the days after it, so
+    /// today gets a share from every missed day in the catch-up window.
+    /// Reviews carried to a day that's missed too aren't carried again, and
+    /// days when everything was paused have nothing to carry.
+    fn carried_over(&self, catch_up: &CatchUp, today: &VersesForADay) -> Vec<CarriedOver<'a>> {
+        let current: HashMap<_, _> = self
+            .verses
+            .iter()
+            .map(|verse| (*verse.reference, verse))
+            .collect();
+        let scheduled: HashSet<_> = [&today.weekly, &today.monthly, &today.yearly]
+            .into_iter()
+            .flatten()
+            .map(|verse| *verse.reference)
+            .collect();
+        (1..=catch_up.days)
+            .rev()
+            .filter_map(|k| {
+                let missed = self.date.checked_sub_days(Days::new(k as u64))?;
+                if !catch_up.is_missed(missed) {
+                    return None;
+                }
+                let day = self.on(missed).scheduled_today();
+                let items = [
+                    (Frequency::Weekly, day.weekly),
+                    (Frequency::Monthly, day.monthly),
+                    (Frequency::Yearly, day.yearly),
+                ]
+                .into_iter()
+                .flat_map(|(phase, verses)| {
+                    verses.into_iter().map(move |verse| CarriedOver {
+                        verse,
+                        phase,
+                        missed,
+                    })
+                })
+                .collect_vec();
+                let shares = split_by_weight(items, catch_up.days, |c| c.verse.weight());
+                shares.into_iter().nth(k - 1)
+            })
+            .flatten()
+            .filter(|c| !scheduled.contains(&c.verse.reference))
+            // Shown as they are today rather than on the day they were missed.
+            .filter_map(|c| {
+                let verse = (*current.get(&c.verse.reference)?).clone();
+                Some(CarriedOver { verse, ..c })
+            })
+            .collect()
+    }
+}
+
+/// Frost's fixed daily, weekly, monthly ladder.
+impl<'a> Scheduler<'a> for ScheduledVerses<'a> {
+    fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    fn for_today(&self) -> VersesForADay<'a> {
+        ScheduledVerses::for_today(self)
+    }
+
+    fn intervals(&self) -> Vec<(Frequency, i64, Verse<'a>)> {
+        self.verses
+            .iter()
+            .filter_map(|verse| {
+                let phase = verse.frequency(&self.config);
+                let interval = self.config.interval(phase)?;
+                Some((phase, interval, verse.clone()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ten verses that are well into their monthly phase on `today`.
+    fn monthly_entries(today: NaiveDate) -> Vec<VerseEntry> {
+        (0..10)
+            .map(|i| {
+                let reference = Reference::new(Book::John, 1, i as u16 + 1);
+                VerseEntry::from_date(today - chrono::Days::new(7 * (40 + i)), reference)
+            })
+            .collect_vec()
+    }
+
+    /// `n` verses that start a week apart from `start`, so they reach
+    /// every phase in turn.
+    pub(crate) fn weekly_entries(start: NaiveDate, n: u64) -> Vec<VerseEntry> {
+        (0..n)
+            .map(|i| {
+                let reference = Reference::new(Book::Psalms, 119, i as u16 + 1);
+                VerseEntry::from_date(start + chrono::Days::new(7 * i), reference)
+            })
+            .collect_vec()
+    }
+
+    fn references(entries: &[VerseEntry]) -> Vec<Reference> {
+        entries
+            .iter()
+            .map(|entry| *entry.reference())
+            .sorted()
+            .collect_vec()
+    }
+
+    #[test]
+    fn monthly_verses_are_each_reviewed_once_per_cycle() {
+        let today = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let entries = monthly_entries(today);
+        let verses = ScheduledVerses::from_date(today, &entries);
+
+        let reviewed = verses
+            .monthly_schedule()
+            .weeks()
+            .iter()
+            .flat_map(|week| week.days())
+            .flat_map(|day| day.monthly())
+            .map(|verse| *verse.reference())
+            .sorted()
+            .collect_vec();
+        assert_eq!(reviewed, references(&entries));
+    }
+
+    #[test]
+    fn verses_are_split_into_days_by_weight() {
+        let sizes = |parts: &[Vec<usize>]| parts.iter().map(Vec::len).collect_vec();
+        let parts = split_by_weight((0..10).collect_vec(), 4, |_| 1);
+        assert_eq!(sizes(&parts), [3, 3, 2, 2]);
+        assert_eq!(parts.concat(), (0..10).collect_vec());
+
+        // More parts than items leaves the last ones empty.
+        let parts = split_by_weight(vec![1, 2], 4, |_| 1);
+        assert_eq!(parts, [vec![1], vec![2], vec![], vec![]]);
+
+        // A six-verse range takes a day to itself, as six single verses would.
+        let verse = |n| Reference::new(Book::John, 3, n);
+        let range = Reference::range(Book::John, 3, 16, 21);
+        let verses = vec![
+            verse(1),
+            range,
+            verse(2),
+            verse(3),
+            verse(4),
+            verse(5),
+            verse(6),
+            verse(7),
+        ];
+        let parts = split_by_weight(verses, 7, |r: &Reference| r.verse_count() as usize);
+        assert_eq!(parts[1], [range]);
+        let weights = parts
+            .iter()
+            .map(|part| part.iter().map(|r| r.verse_count()).sum::<u16>())
+            .collect_vec();
+        assert_eq!(weights, [1, 6, 2, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn both_month_modes_review_each_monthly_verse_once_per_month() {
+        let today = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let entries = monthly_entries(today);
+        let verses = ScheduledVerses::from_date(today, &entries);
+
+        let four_week = verses.monthly_schedule();
+        let four_week = four_week.weeks().iter().flat_map(|week| week.days());
+        let calendar = verses.calendar_month_schedule();
+
+        assert_eq!(four_week.clone().count(), 28);
+        assert_eq!(calendar.days().len(), 31);
+        for days in [four_week.collect_vec(), calendar.days().iter().collect()] {
+            let reviewed = days
+                .iter()
+                .flat_map(|day| day.monthly())
+                .map(|verse| *verse.reference())
+                .sorted()
+                .collect_vec();
+            assert_eq!(reviewed, references(&entries));
+        }
+    }
+
+    #[test]
+    fn for_today_matches_the_monthly_schedule_for_any_week_start() {
+        let start = NaiveDate::from_ymd_opt(2030, 1, 2).unwrap();
+        let entries = weekly_entries(start, 60);
+        let today = start + chrono::Days::new(7 * 45 + 3);
+        for week_start in [Weekday::Sun, Weekday::Mon, Weekday::Wed] {
+            let config = ScheduleConfig {
+                week_start,
+                ..ScheduleConfig::frost()
+            };
+            for date in today.iter_days().take(14) {
+                let verses = ScheduledVerses::from_date(date, &entries).with_config(config);
+                let month = verses.monthly_schedule();
+                let days = month.weeks().iter().flat_map(|week| week.days());
+                let scheduled = days
+                    .filter(|day| day.date() == Some(date))
+                    .exactly_one()
+                    .unwrap();
+                let refs = |verses: &[Verse]| verses.iter().map(|v| *v.reference()).collect_vec();
+                let today = verses.for_today();
+                assert_eq!(refs(scheduled.weekly()), refs(today.weekly()));
+                assert_eq!(refs(scheduled.monthly()), refs(today.monthly()));
+                assert_eq!(config.start_of_week(date).weekday(), week_start);
+            }
+        }
+    }
+
+    #[test]
+    fn cycle_follows_the_anchor_not_the_order_of_entries() {
+        let today = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let mut entries = monthly_entries(today);
+        let offset = ScheduledVerses::from_date(today, &entries).current_week_offset();
+        let monthly = |entries: &[VerseEntry]| {
+            let verses = ScheduledVerses::from_date(today, entries);
+            let month = verses.monthly_schedule();
+            month
+                .weeks()
+                .iter()
+                .flat_map(|week| week.days())
+                .map(|day| day.monthly().iter().map(|v| *v.reference()).collect_vec())
+                .collect_vec()
+        };
+        let before = monthly(&entries);
+
+        entries.reverse();
+        entries.swap(2, 7);
+        let verses = ScheduledVerses::from_date(today, &entries);
+        assert_eq!(verses.current_week_offset(), offset);
+        assert_eq!(monthly(&entries), before);
+
+        let anchored = verses.with_anchor(today - chrono::Days::new(7));
+        assert_eq!(anchored.current_week_offset(), 1);
+    }
+
+    #[test]
+    fn phases_change_on_the_exact_day() {
+        // A Wednesday, while weeks start on Sunday.
+        let start = NaiveDate::from_ymd_opt(2030, 1, 9).unwrap();
+        let entries = [VerseEntry::from_date(
+            start,
+            Reference::new(Book::John, 3, 16),
+        )];
+        let daily = |days: i64| {
+            let date = start + chrono::Duration::days(days);
+            let verses = ScheduledVerses::from_date(date, &entries).with_anchor(start);
+            !verses.for_today().daily().is_empty()
+        };
+        assert!(!daily(-1));
+        assert!(daily(0));
+        assert!(daily(48));
+        assert!(!daily(49));
+    }
+
+    #[test]
+    fn phase_lengths_follow_the_config() {
+        let start = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let entry = VerseEntry::from_date(start, Reference::new(Book::John, 3, 16));
+        let config = ScheduleConfig {
+            daily_weeks: 2,
+            weekly_weeks: 4,
+            monthly_weeks: 10,
+            ..ScheduleConfig::frost()
+        };
+        let frequency = |weeks: u64, config: &ScheduleConfig| {
+            entry.frequency(start + Days::new(7 * weeks), config)
+        };
+        assert_eq!(frequency(1, &config), Frequency::Daily);
+        assert_eq!(frequency(2, &config), Frequency::Weekly);
+        assert_eq!(frequency(6, &config), Frequency::Monthly);
+        assert_eq!(frequency(16, &config), Frequency::Done);
+
+        let frost = ScheduleConfig::frost();
+        assert_eq!(frequency(2, &frost), Frequency::Daily);
+        assert_eq!(frequency(6, &frost), Frequency::Daily);
+        assert_eq!(frequency(16, &frost), Frequency::Weekly);
+    }
+
+    #[test]
+    fn yearly_verses_are_reviewed_once_per_cycle() {
+        let start = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let entries = [VerseEntry::from_date(
+            start,
+            Reference::new(Book::John, 3, 16),
+        )];
+        let config = ScheduleConfig {
+            daily_weeks: 1,
+            weekly_weeks: 1,
+            monthly_weeks: 1,
+            yearly_weeks: Some(3),
+            ..ScheduleConfig::frost()
+        };
+        let yearly_days = |config: ScheduleConfig| {
+            (21..84)
+                .filter(|day| {
+                    let date = start + Days::new(*day);
+                    let verses = ScheduledVerses::from_date(date, &entries).with_config(config);
+                    !verses.for_today().yearly().is_empty()
+                })
+                .count()
+        };
+        // Weeks 3, 6 and 9 after the verse started.
+        assert_eq!(yearly_days(config), 3);
+        assert_eq!(
+            yearly_days(ScheduleConfig {
+                yearly_weeks: None,
+                ..config
+            }),
+            0
+        );
+        assert_eq!(
+            yearly_days(ScheduleConfig {
+                yearly_weeks: Some(0),
+                ..config
+            }),
+            0
+        );
+    }
+
+    #[test]
+    fn pauses_push_phases_back_by_their_length() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2030, m, d).unwrap();
+        let start = date(1, 6);
+        let own = Pause::new(date(1, 10), date(1, 16));
+        let entry = VerseEntry::from_date(start, Reference::new(Book::John, 3, 16)).with_pause(own);
+        let plan = [Pause::new(date(1, 14), date(1, 20))];
+
+        // Overlapping days are only counted once, and a day isn't counted
+        // until it's over.
+        assert_eq!(entry.days_in(date(1, 13)), 4);
+        assert_eq!(entry.active_days_in(date(1, 21), &plan), 4);
+        assert!(entry.is_paused(date(1, 18), &plan));
+        assert!(!entry.is_paused(date(1, 18), &[]));
+
+        // 49 active days in the daily phase, plus the 11 paused ones.
+        let daily = |days| {
+            let entries = [&entry];
+            let verses =
+                ScheduledVerses::from_date_with_pauses(start + Days::new(days), entries, &plan);
+            !verses.for_today().daily().is_empty()
+        };
+        assert!(!daily(4));
+        assert!(daily(15));
+        assert!(daily(59));
+        assert!(!daily(60));
+    }
+
+    #[test]
+    fn missed_reviews_are_spread_over_the_next_days() {
+        let start = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        // 60 verses a week apart, so there are weekly and monthly ones.
+        let entries = weekly_entries(start, 60);
+        let today = start + Days::new(7 * 50);
+        let missed = today + Days::new(1);
+        let completed = [today, missed + Days::new(1), missed + Days::new(2)];
+        let catch_up = CatchUp::new(completed.into_iter().collect(), 3);
+
+        let verses = ScheduledVerses::from_date(missed, &entries);
+        let day = verses.for_today();
+        let mut expected = [day.weekly(), day.monthly()]
+            .concat()
+            .iter()
+            .map(|verse| *verse.reference())
+            .collect_vec();
+        expected.sort();
+
+        let mut carried = vec![];
+        for k in 1..=3 {
+            let verses = ScheduledVerses::from_date(missed + Days::new(k), &entries)
+                .with_catch_up(catch_up.clone());
+            let day = verses.for_today();
+            assert!(day.carried_over().len().abs_diff(expected.len() / 3) <= 1);
+            assert!(day.carried_over().iter().all(|c| c.missed() == missed));
+            carried.extend(day.carried_over().iter().map(|c| *c.verse().reference()));
+        }
+        carried.sort();
+        assert_eq!(carried, expected);
+        assert!(!expected.is_empty());
+    }
+
+    #[test]
+    fn paused_days_are_not_caught_up() {
+        let start = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let day = |n| start + Days::new(n);
+        let entries = weekly_entries(start, 60);
+        let pauses = [Pause::new(day(351), day(360))];
+        let completed: BTreeSet<_> = [day(350), day(361)].into_iter().collect();
+        let schedule = |n, window| {
+            ScheduledVerses::from_date_with_pauses(day(n), &entries, &pauses)
+                .with_catch_up(CatchUp::new(completed.clone(), window))
+                .for_today()
+        };
+        let reviews = |day: &VersesForADay| {
+            [day.weekly(), day.monthly(), day.yearly()]
+                .concat()
+                .iter()
+                .map(|verse| *verse.reference())
+                .collect::<BTreeSet<_>>()
+        };
+        // Something would have been due on the paused days without the pause.
+        let unpaused = ScheduledVerses::from_date(day(359), &entries).for_today();
+        assert!(!reviews(&unpaused).is_empty());
+
+        assert!(schedule(361, 14).carried_over().is_empty());
+        assert!(schedule(362, 14).carried_over().is_empty());
+
+        // Day 362 is missed, so its reviews, as scheduled after the pause,
+        // move to day 363.
+        let today = schedule(363, 1);
+        let carried: BTreeSet<_> = today
+            .carried_over()
+            .iter()
+            .map(|c| *c.verse().reference())
+            .collect();
+        let missed =
+            ScheduledVerses::from_date_with_pauses(day(362), &entries, &pauses).for_today();
+        let expected: BTreeSet<_> = reviews(&missed)
+            .difference(&reviews(&today))
+            .copied()
+            .collect();
+        assert!(!expected.is_empty());
+        assert_eq!(carried, expected);
+        assert!(today.carried_over().iter().all(|c| c.missed() == day(362)));
+    }
+
+    #[test]
+    fn days_serialize_with_phase_and_date_on_each_verse() {
+        let start = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let entries = weekly_entries(start, 60);
+        let missed = start + Days::new(7 * 50 + 1);
+        let today = missed + Days::new(1);
+        let completed = [missed - Days::new(1), today];
+        let verses = ScheduledVerses::from_date(today, &entries)
+            .with_catch_up(CatchUp::new(completed.into_iter().collect(), 1));
+        let day = verses.for_today();
+        assert!(!day.carried_over().is_empty());
+
+        let json = serde_json::to_value(&day).unwrap();
+        assert_eq!(json["date"], today.to_string());
+        let check = |list: &str, verses: &[Verse], phase: &str| {
+            let items = json[list].as_array().unwrap();
+            assert_eq!(items.len(), verses.len(), "{list}");
+            for (item, verse) in items.iter().zip(verses) {
+                assert_eq!(item["reference"], verse.reference().to_string());
+                assert_eq!(item["weeks_in"], verse.weeks_in());
+                assert_eq!(item["phase"], phase);
+                assert_eq!(item["date"], today.to_string());
+                assert!(item.get("missed").is_none());
+            }
+        };
+        check("daily", day.daily(), "daily");
+        check("weekly", day.weekly(), "weekly");
+        check("monthly", day.monthly(), "monthly");
+        check("yearly", day.yearly(), "yearly");
+
+        let carried = json["carried_over"].as_array().unwrap();
+        assert_eq!(carried.len(), day.carried_over().len());
+        for (item, c) in carried.iter().zip(day.carried_over()) {
+            assert_eq!(item["reference"], c.verse().reference().to_string());
+            assert_eq!(item["weeks_in"], c.verse().weeks_in());
+            assert_eq!(item["phase"], c.phase().to_string());
+            assert_eq!(item["date"], today.to_string());
+            assert_eq!(item["missed"], missed.to_string());
+        }
+    }
+
+    #[test]
+    fn calendar_mode_follows_the_gregorian_month() {
+        let today = NaiveDate::from_ymd_opt(2030, 2, 14).unwrap();
+        let entries = monthly_entries(today);
+        let config = ScheduleConfig {
+            month_mode: MonthMode::Calendar,
+            ..ScheduleConfig::frost()
+        };
+        let verses = ScheduledVerses::from_date(today, &entries).with_config(config);
+
+        let month = verses.calendar_month_schedule();
+        let first = month.days().first().and_then(|day| day.date());
+        let last = month.days().last().and_then(|day| day.date());
+        assert_eq!(first, NaiveDate::from_ymd_opt(2030, 2, 1));
+        assert_eq!(last, NaiveDate::from_ymd_opt(2030, 2, 28));
+
+        // Ten verses over 28 days: one each on the first ten days, none after.
+        let monthly = |day: usize| month.days()[day].monthly().len();
+        assert!((0..10).all(|day| monthly(day) == 1));
+        assert!((10..28).all(|day| monthly(day) == 0));
+
+        let today = verses.for_today();
+        assert_eq!(
+            today.date(),
+            Some(NaiveDate::from_ymd_opt(2030, 2, 14).unwrap())
+        );
+        assert!(today.monthly().is_empty());
+    }
+}
@@ -1,360 +1,644 @@
-#![allow(unused)]
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-pub type AnyResult<T> = Result<T, Box<dyn std::error::Error>>;
-
-use std::borrow::Cow;
-
-use chrono::{Datelike, NaiveDate};
+use chrono::{Datelike, Local, NaiveDate, Utc, Weekday};
+use clap::{Parser, Subcommand, ValueEnum};
 use itertools::Itertools;
-
-fn split_into_n_parts<T: Clone>(vec: Vec<T>, n: usize) -> Vec<Vec<T>> {
-    let len = vec.len();
-    let base = len / n;
-    let remainder = len % n;
-
-    let mut result = Vec::with_capacity(n);
-    let mut start = 0;
-
-    for i in 0..n {
-        let extra = if i < remainder { 1 } else { 0 };
-        let end = start + base + extra;
-        result.push(vec[start..end].to_vec());
-        start = end;
-    }
-
-    result
+use scripture_retention_algorithm::adherence::Adherence;
+use scripture_retention_algorithm::booklet;
+use scripture_retention_algorithm::calendar::CalendarExport;
+use scripture_retention_algorithm::forecast::{Forecast, Granularity};
+use scripture_retention_algorithm::generate::{Passage, PlanGenerator, SkipRule};
+use scripture_retention_algorithm::parser::{Diagnostic, parse_dates};
+use scripture_retention_algorithm::review::{Grade, Outcome, Review, ReviewLog};
+use scripture_retention_algorithm::srs::{Fsrs, Sm2, SpacedRepetition};
+use scripture_retention_algorithm::versification::Versification;
+use scripture_retention_algorithm::{
+    CatchUp, Error, FMT, MonthMode, Plan, Reference, Result, ScheduleConfig, Scheduler,
+    VersesForACalendarMonth, VersesForADay, VersesForAMonth, parse_plan, parse_plan_strict,
+};
+
+/// Which date a command is scheduled for, defaulting to the system's local date.
+fn today() -> NaiveDate {
+    Local::now().date_naive()
 }
 
-#[derive(Debug)]
-pub struct VerseEntry {
-    date: NaiveDate,
-    reference: String,
+fn parse_date(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, FMT).map_err(|e| format!("expected YYYY-MM-DD: {e}"))
 }
 
-const FMT: &'static str = "%Y-%m-%d";
-
-impl VerseEntry {
-    pub fn new(date: &str, reference: impl Into<String>) -> AnyResult<Self> {
-        let reference = reference.into();
-        let date = NaiveDate::parse_from_str(date, FMT)?;
-        Ok(Self { date, reference })
-    }
-
-    pub fn weeks_in(&self, today: NaiveDate) -> i64 {
-        (today - self.date).num_weeks()
-    }
-
-    pub fn frequency(&self, today: NaiveDate) -> Frequency {
-        Frequency::new(self.weeks_in(today))
-    }
-
-    pub fn calculate_relative(&self, today: NaiveDate) -> Verse {
-        let weeks_in = self.weeks_in(today);
-        Verse {
-            weeks_in,
-            reference: Cow::Owned(self.reference.clone()),
-        }
-    }
+fn parse_date_range(range: &str) -> Result<(NaiveDate, NaiveDate), String> {
+    let (from, to) = range
+        .split_once("..")
+        .ok_or_else(|| "expected YYYY-MM-DD..YYYY-MM-DD".to_string())?;
+    Ok((parse_date(from)?, parse_date(to)?))
 }
 
-#[derive(Debug)]
-pub struct VerseList {
-    today: NaiveDate,
-    references: Vec<VerseEntry>,
+/// Where each week starts: a fixed weekday, or the weekday of the plan's
+/// earliest verse.
+#[derive(Clone, Copy, Debug)]
+enum WeekStart {
+    Day(Weekday),
+    Plan,
 }
 
-impl VerseList {
-    pub fn new(date: &str, references: Vec<VerseEntry>) -> AnyResult<Self> {
-        let today = NaiveDate::parse_from_str(date, FMT)?;
-        Ok(Self { today, references })
-    }
-
-    pub fn relative_verses(&self) -> Vec<Verse> {
-        self.references
-            .iter()
-            .map(|verse| verse.calculate_relative(self.today))
-            .collect()
+fn parse_week_start(start: &str) -> Result<WeekStart, String> {
+    if start.eq_ignore_ascii_case("plan") {
+        return Ok(WeekStart::Plan);
     }
+    start
+        .parse()
+        .map(WeekStart::Day)
+        .map_err(|_| format!("expected a weekday or `plan`, got `{start}`"))
 }
 
-#[derive(Clone, Debug)]
-pub struct Verse<'a> {
-    weeks_in: i64,
-    reference: Cow<'a, String>,
+#[derive(Parser, Debug)]
+#[command(about = "Scripture memorization and meditation schedule")]
+struct Cli {
+    /// Verse list, one `YYYY-MM-DD | Reference` per line
+    #[arg(short, long, global = true, default_value = "input.txt")]
+    input: PathBuf,
+
+    /// Date to schedule for (defaults to today)
+    #[arg(short, long, global = true, value_parser = parse_date)]
+    date: Option<NaiveDate>,
+
+    /// Weeks a new verse is reviewed every day
+    #[arg(
+        long,
+        global = true,
+        default_value_t = ScheduleConfig::frost().daily_weeks,
+        value_parser = clap::value_parser!(i64).range(0..)
+    )]
+    daily_weeks: i64,
+
+    /// Weeks a verse is reviewed once a week after its daily phase
+    #[arg(
+        long,
+        global = true,
+        default_value_t = ScheduleConfig::frost().weekly_weeks,
+        value_parser = clap::value_parser!(i64).range(0..)
+    )]
+    weekly_weeks: i64,
+
+    /// Weeks a verse is reviewed once a month after its weekly phase
+    #[arg(
+        long,
+        global = true,
+        default_value_t = ScheduleConfig::frost().monthly_weeks,
+        value_parser = clap::value_parser!(i64).range(0..)
+    )]
+    monthly_weeks: i64,
+
+    /// Keep reviewing verses once per this many weeks after their monthly phase
+    #[arg(long, global = true, value_parser = clap::value_parser!(i64).range(1..))]
+    yearly_weeks: Option<i64>,
+
+    /// Spread monthly verses over a 4-week cycle (four-week) or over the
+    /// days of the Gregorian month (calendar). Overrides the plan's
+    /// `@month-mode`, which otherwise defaults to four-week
+    #[arg(long, global = true)]
+    month_mode: Option<MonthMode>,
+
+    /// First day of the week (e.g. sunday, monday), or `plan` for the weekday
+    /// the plan started on
+    #[arg(long, global = true, default_value = "sunday", value_parser = parse_week_start)]
+    week_start: WeekStart,
+
+    /// Days reviewed so far, one `YYYY-MM-DD` per line. Weekly and monthly
+    /// reviews from days missing from it are carried over to later days
+    #[arg(long, global = true)]
+    completed: Option<PathBuf>,
+
+    /// Review log (defaults to `<input>.reviews.jsonl` next to the verse list)
+    #[arg(long, global = true)]
+    log: Option<PathBuf>,
+
+    /// Move verses through their daily and weekly phases faster or slower
+    /// according to the grades in the review log
+    #[arg(long, global = true)]
+    adaptive: bool,
+
+    /// How many days to spread a missed day's reviews over
+    #[arg(long, global = true, default_value_t = 3)]
+    catch_up: usize,
+
+    /// Fail on any malformed line instead of skipping it with a warning
+    #[arg(long, global = true)]
+    strict: bool,
+
+    /// Chapter and verse numbering to check references against (kjv or vulgate)
+    #[arg(long, global = true, default_value = "kjv")]
+    versification: Versification,
+
+    /// Algorithm that picks each day's verses
+    #[arg(long, global = true, value_enum, default_value_t = SchedulerKind::Frost)]
+    scheduler: SchedulerKind,
+
+    /// Output format
+    #[arg(short, long, global = true, value_enum, default_value_t = Format::Markdown)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
 }
 
-impl<'a> Verse<'a> {
-    pub fn frequency(&self) -> Frequency {
-        Frequency::new(self.weeks_in)
-    }
-
-    pub fn add_offset(&mut self, weeks: i64) {
-        self.weeks_in += weeks;
-    }
-
-    pub fn with_offset(&self, weeks: i64) -> Self {
-        let mut it = self.clone();
-        it.weeks_in += weeks;
-        it
-    }
-
-    pub fn is_daily(&self) -> bool {
-        self.frequency() == Frequency::Daily
-    }
-
-    pub fn is_weekly(&self) -> bool {
-        self.frequency() == Frequency::Weekly
-    }
-
-    pub fn is_monthly(&self) -> bool {
-        self.frequency() == Frequency::Monthly
-    }
-
-    pub fn will_be_monthly_this_month(&self, n: i64) -> bool {
-        self.is_monthly() || self.with_offset(n).is_monthly()
-    }
-
-    pub fn is_monthly_week(&self, n: i64) -> bool {
-        let is_monthly = self.frequency() == Frequency::Monthly;
-        let is_monthly_this_week = self.weeks_in % 4 == n;
-        is_monthly && is_monthly_this_week
-    }
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Verses to review today (or on `--date`)
+    Today,
+    /// Verses to review on a given day
+    Day {
+        #[arg(value_parser = parse_date)]
+        date: NaiveDate,
+    },
+    /// Verses to review on every day from `from` to `to`, inclusive
+    Range {
+        #[arg(value_parser = parse_date)]
+        from: NaiveDate,
+        #[arg(value_parser = parse_date)]
+        to: NaiveDate,
+    },
+    /// Every day of the month containing a given day
+    Month {
+        #[arg(value_parser = parse_date)]
+        date: NaiveDate,
+    },
+    /// Daily/weekly/monthly counts for each day of the current month
+    Stats,
+    /// Review counts per day and week from `from` to `to`, with averages and
+    /// peaks
+    Forecast {
+        #[arg(value_parser = parse_date)]
+        from: NaiveDate,
+        #[arg(value_parser = parse_date)]
+        to: NaiveDate,
+        /// List counts by day or by week
+        #[arg(long, default_value = "week")]
+        by: Granularity,
+        /// Also write a bar chart of the counts to this SVG file
+        #[arg(long)]
+        svg: Option<PathBuf>,
+    },
+    /// Streaks, completion rates and overdue verses from the review log
+    Adherence,
+    /// Printable HTML booklet of the month containing a given day
+    Booklet {
+        #[arg(value_parser = parse_date)]
+        date: NaiveDate,
+        /// Write the booklet here instead of to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Export the schedule from `from` to `to` as an iCalendar (.ics) file
+    Ics {
+        #[arg(value_parser = parse_date)]
+        from: NaiveDate,
+        #[arg(value_parser = parse_date)]
+        to: NaiveDate,
+        /// Write the calendar here instead of to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Record today's (or `--date`'s) verses as reviewed in the review log
+    Done {
+        /// Only record these verses (defaults to all of the day's verses)
+        references: Vec<String>,
+        /// Record the verses as skipped instead
+        #[arg(long, conflicts_with = "grade")]
+        skipped: bool,
+        /// How well the verses were recalled: again, hard, good or easy
+        #[arg(long)]
+        grade: Option<Grade>,
+    },
+    /// Every review of a verse recorded in the review log
+    History { reference: String },
+    /// Write a verse list that memorizes a passage at a steady pace
+    Generate {
+        /// Passage to memorize, e.g. "John 1:1 - John 21:25" or "Philippians"
+        passage: String,
+        /// Date of the first new verse (defaults to `--date` or today)
+        #[arg(long, value_parser = parse_date)]
+        start: Option<NaiveDate>,
+        /// New verses started each week
+        #[arg(long, default_value_t = 1)]
+        per_week: usize,
+        /// Don't start new verses between two dates, e.g. 2025-12-21..2026-01-04
+        #[arg(long, value_parser = parse_date_range)]
+        skip_between: Vec<(NaiveDate, NaiveDate)>,
+        /// Don't start new verses every nth week
+        #[arg(long)]
+        skip_every: Option<u32>,
+        /// Leave a passage out of the plan, e.g. "John 7:53 - 8:11"
+        #[arg(long)]
+        skip: Vec<String>,
+        /// Write the verse list here instead of to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
-pub struct RelativeVerseList {}
+/// Which algorithm picks each day's verses.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum SchedulerKind {
+    /// Frost's fixed daily, weekly, monthly ladder
+    Frost,
+    /// SM-2 spaced repetition from the review log
+    Sm2,
+    /// FSRS spaced repetition from the review log
+    Fsrs,
+}
 
-#[derive(PartialEq, Debug)]
-pub enum Frequency {
-    NotStarted,
-    Daily,
-    Weekly,
-    Monthly,
-    Done,
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Markdown,
+    Text,
+    Json,
 }
 
-impl Frequency {
-    pub fn new(weeks_in: i64) -> Self {
-        if weeks_in < 0 {
-            Frequency::NotStarted
-        } else if weeks_in < 7 {
-            Frequency::Daily
-        } else if weeks_in < 7 + 28 {
-            Frequency::Weekly
-        } else if weeks_in < 7 + 28 + 336 {
-            Frequency::Monthly
-        } else {
-            Frequency::Done
+fn render_day(day: &VersesForADay, format: Format) -> String {
+    match format {
+        Format::Markdown => day.data(),
+        Format::Json => serde_json::to_string_pretty(day).expect("days serialize to JSON"),
+        Format::Text => {
+            let mut text = [
+                ("Daily", day.daily()),
+                ("Weekly", day.weekly()),
+                ("Monthly", day.monthly()),
+                ("Yearly", day.yearly()),
+            ]
+            .iter()
+            .map(|(name, verses)| {
+                format!(
+                    "{}: {}",
+                    name,
+                    verses.iter().map(|v| v.reference()).join(", ")
+                )
+            })
+            .join("\n");
+            if !day.carried_over().is_empty() {
+                let carried_over = day
+                    .carried_over()
+                    .iter()
+                    .map(|c| format!("{} (from {})", c.verse().reference(), c.missed()))
+                    .join(", ");
+                text.push_str(&format!("\nCarried over: {carried_over}"));
+            }
+            text
         }
     }
 }
 
-#[derive(Clone, Debug, Default)]
-pub struct VersesForADay<'a> {
-    daily: Vec<Verse<'a>>,
-    weekly: Vec<Verse<'a>>,
-    monthly: Vec<Verse<'a>>,
-}
-
-impl<'a> VersesForADay<'a> {
-    pub fn data(&self) -> String {
-        let daily = self.daily.iter().map(|v| &v.reference).join("\n- ");
-        let weekly = self.weekly.iter().map(|v| &v.reference).join("\n- ");
-        let monthly = self.monthly.iter().map(|v| &v.reference).join("\n- ");
-        vec![
-            format!("### Daily: \n- {}", daily),
-            format!("### Weekly: \n- {}", weekly),
-            format!("### Monthly: \n- {}", monthly),
-        ]
-        .join("\n\n")
+fn print_day(date: NaiveDate, day: &VersesForADay, format: Format) {
+    match format {
+        Format::Markdown => println!("## {}\n\n{}\n", date, render_day(day, format)),
+        Format::Text => println!("{}\n{}\n", date, render_day(day, format)),
+        Format::Json => println!("{}", render_day(day, format)),
     }
 }
 
-#[derive(Debug)]
-pub struct VersesForAWeek<'a> {
-    days: Vec<VersesForADay<'a>>,
-}
-
-impl<'a> VersesForAWeek<'a> {
-    pub fn new<'b>(verses: &'b Vec<Verse<'a>>, n: i64) -> Self {
-        let daily: Vec<_> = verses
-            .iter()
-            .filter(|verse| verse.is_daily())
-            .cloned()
-            .collect();
-
-        let weekly: Vec<_> = verses
-            .iter()
-            .filter(|verse| verse.with_offset(n).is_weekly())
-            .cloned()
-            .collect();
-
-        let monthly: Vec<_> = verses
-            .iter()
-            // I actually want this to be "is monthly" or "will be monthly this month"
-            // .filter(|verse| verse.is_monthly())
-            .filter(|verse| verse.will_be_monthly_this_month(n))
-            .cloned()
-            .collect_vec();
-        let bin = monthly.len() / 4;
-        let monthly = monthly
-            .into_iter()
-            .skip(n as usize * bin)
-            .take(bin)
-            .collect_vec();
-
-        let weekly = split_into_n_parts(weekly, 7);
-        let monthly = split_into_n_parts(monthly, 7);
-        let days = weekly
-            .into_iter()
-            .zip(monthly)
-            .map(|(weekly, monthly)| VersesForADay {
-                daily: daily.clone(),
-                weekly,
-                monthly,
-            })
-            .collect_vec();
-        Self { days }
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let source_name = cli.input.display().to_string();
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", Diagnostic::error(&e, &source_name));
+            ExitCode::FAILURE
+        }
     }
 }
 
-#[derive(Debug)]
-pub struct VersesForAMonth<'a> {
-    weeks: Vec<VersesForAWeek<'a>>,
-}
-
-impl<'a> VersesForAMonth<'a> {
-    pub fn new(verses: &'a Vec<Verse>) -> Self {
-        let weeks = (0..=3)
-            .map(|n| VersesForAWeek::new(&verses, n))
-            .collect_vec();
-        Self { weeks }
+/// Reads the verse list, printing a warning for each malformed line unless
+/// `strict` is set, in which case they're all returned as one error.
+fn load_plan(path: &Path, strict: bool, versification: Versification) -> Result<Plan> {
+    let input = std::fs::read_to_string(path)?;
+    if strict {
+        return parse_plan_strict(&input, versification);
     }
-
-    pub fn stats(&self) -> String {
-        self.weeks
-            .iter()
-            .map(|week| {
-                week.days
-                    .iter()
-                    .map(|day| {
-                        format!(
-                            "D: {} | W: {} | M: {}\n{}",
-                            // "D: {} | W: {} | M: {}",
-                            day.daily.len(),
-                            day.weekly.len(),
-                            day.monthly.len(),
-                            day.monthly.iter().map(|v| &v.reference).join(" + "),
-                        )
-                    })
-                    .join("\n")
-            })
-            .join("\n---\n")
+    let (plan, errors) = parse_plan(&input, versification);
+    let source_name = path.display().to_string();
+    for error in errors {
+        eprintln!("{}\n", Diagnostic::warning(&error, &source_name));
     }
+    Ok(plan)
 }
 
-#[derive(Debug)]
-pub struct ScheduledVerses<'a> {
-    date: NaiveDate,
-    verses: Vec<Verse<'a>>,
-}
-impl<'a> ScheduledVerses<'a> {
-    pub fn new(
-        date: &str,
-        verses: impl IntoIterator<Item = &'a VerseEntry> + 'a,
-    ) -> AnyResult<Self> {
-        let date = NaiveDate::parse_from_str(date, FMT)?;
-        let verses = verses
-            .into_iter()
-            .map(|verse| verse.calculate_relative(date))
-            .collect();
-        Ok(Self { date, verses })
+/// Reads the list of completed days, printing a warning for each bad line.
+fn load_completed(path: &Path) -> Result<BTreeSet<NaiveDate>> {
+    let input = std::fs::read_to_string(path)?;
+    let (dates, errors) = parse_dates(&input);
+    let source_name = path.display().to_string();
+    for error in errors {
+        eprintln!("{}\n", Diagnostic::warning(&error, &source_name));
     }
+    Ok(dates)
+}
 
-    pub fn monthly_schedule(&'a self) -> VersesForAMonth<'a> {
-        VersesForAMonth::new(&self.verses)
+/// Reads the review log, printing a warning for each bad line. A log that
+/// doesn't exist yet is empty.
+fn load_log(path: &Path) -> Result<ReviewLog> {
+    if !path.exists() {
+        return Ok(ReviewLog::default());
     }
-
-    pub fn current_week_offset(&self) -> usize {
-        self.verses
-            .first()
-            .map(|v| {
-                if v.weeks_in < 0 {
-                    0
-                } else {
-                    (v.weeks_in % 4) as usize
-                }
-            })
-            .unwrap_or(0)
+    let input = std::fs::read_to_string(path)?;
+    let (log, errors) = ReviewLog::parse(&input);
+    let source_name = path.display().to_string();
+    for error in errors {
+        eprintln!("{}\n", Diagnostic::warning(&error, &source_name));
     }
+    Ok(log)
+}
 
-    pub fn for_today(&'a self) -> VersesForADay<'a> {
-        let week = self.current_week_offset();
-        let m = self.monthly_schedule();
-        let week = m.weeks.get(week);
-
-        let result = week
-            .map(|week| {
-                week.days
-                    .get(self.date.weekday().num_days_from_sunday() as usize)
-                    .cloned()
-            })
-            .flatten()
-            .unwrap_or_default();
-
-        result
+fn print_reviews(reviews: &[Review], format: Format) -> Result<()> {
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(reviews)?),
+        _ => {
+            for review in reviews {
+                let mut line = format!(
+                    "{} | {} | {} | {}",
+                    review.date(),
+                    review.reference(),
+                    review.phase(),
+                    review.outcome()
+                );
+                if let Some(grade) = review.grade() {
+                    line.push_str(&format!(" | {grade}"));
+                }
+                println!("{line}");
+            }
+        }
     }
+    Ok(())
 }
 
-fn main() -> AnyResult<()> {
-    // let date = "2025-07-06";
-    let date = "2033-02-06";
-
-    let references = std::fs::read_to_string(
-        "/home/dgmastertemple/Development/rust/scripture_retention_algorithm/input.txt",
-    )?
-    .lines()
-    .filter_map(|line| {
-        line.trim()
-            .split_once(" | ")
-            .and_then(|(date, verse)| VerseEntry::new(date, verse).ok())
-    })
-    .collect_vec();
-
-    // let list = VerseList::new(date, references)?;
-    // let verses = list.relative_verses();
-
-    // let verses = ScheduledVerses::new("2025-06-28", &references)?;
-    // println!("{}", verses.monthly_schedule().stats());
-    //
-    // let verses = ScheduledVerses::new("2025-07-06", &references)?;
-    // println!("{}", verses.monthly_schedule().stats());
-    //
-    // let verses = ScheduledVerses::new("2025-07-13", &references)?;
-    // println!("{}", verses.monthly_schedule().stats());
-    // dbg!(&verses.for_today());
-
-    // let days = vec!["2025-08-24"];
-    let days = std::fs::read_to_string(
-        "/home/dgmastertemple/Development/rust/scripture_retention_algorithm/days.txt",
-    )?
-    .lines()
-    .map(|l| l.trim().to_string())
-    .collect_vec();
-
-    for day in days {
-        let verses = ScheduledVerses::new(day.as_str(), &references)?;
-        // dbg!(&verses.for_today());
-        println!("## {}\n\n{}\n", day, &verses.for_today().data());
+fn run(cli: Cli) -> Result<()> {
+    let date = cli.date.unwrap_or_else(today);
+    let plan = match cli.command {
+        Command::Generate { .. } => Plan::default(),
+        _ => load_plan(&cli.input, cli.strict, cli.versification)?,
+    };
+
+    let week_start = match cli.week_start {
+        WeekStart::Day(day) => day,
+        WeekStart::Plan => plan.anchor().map_or(Weekday::Sun, |date| date.weekday()),
+    };
+    let config = ScheduleConfig {
+        daily_weeks: cli.daily_weeks,
+        weekly_weeks: cli.weekly_weeks,
+        monthly_weeks: cli.monthly_weeks,
+        yearly_weeks: cli.yearly_weeks,
+        month_mode: cli.month_mode.or(plan.month_mode()).unwrap_or_default(),
+        week_start,
+    };
+
+    let catch_up = match &cli.completed {
+        Some(path) => Some(CatchUp::new(load_completed(path)?, cli.catch_up)),
+        None => None,
+    };
+    let log_path = cli
+        .log
+        .clone()
+        .unwrap_or_else(|| ReviewLog::path_for(&cli.input));
+    let reviews = if cli.adaptive || cli.scheduler != SchedulerKind::Frost {
+        load_log(&log_path)?
+    } else {
+        ReviewLog::default()
+    };
+    let schedule = |date| {
+        let mut verses = plan.schedule(date).with_config(config);
+        if cli.adaptive {
+            verses = verses.with_grades(&reviews);
+        }
+        match &catch_up {
+            Some(catch_up) => verses.with_catch_up(catch_up.clone()),
+            None => verses,
+        }
+    };
+    let scheduler = |date| -> Box<dyn Scheduler> {
+        match cli.scheduler {
+            SchedulerKind::Frost => Box::new(schedule(date)),
+            SchedulerKind::Sm2 => Box::new(SpacedRepetition::new::<Sm2>(
+                date,
+                plan.entries(),
+                plan.pauses(),
+                &reviews,
+            )),
+            SchedulerKind::Fsrs => Box::new(SpacedRepetition::new::<Fsrs>(
+                date,
+                plan.entries(),
+                plan.pauses(),
+                &reviews,
+            )),
+        }
+    };
+    let for_day = |date| scheduler(date).for_today();
+    // Months are built a day at a time so any scheduler can fill them in.
+    let month_start = |date| config.start_of_month(plan.anchor().unwrap_or(date), date);
+
+    match cli.command {
+        Command::Done {
+            references,
+            skipped,
+            grade,
+        } => {
+            let outcome = if skipped {
+                Outcome::Skipped
+            } else {
+                Outcome::Reviewed
+            };
+            let day = for_day(date);
+            let mut log = load_log(&log_path)?;
+            let references = if references.is_empty() {
+                day.items().map(|(_, verse)| *verse.reference()).collect()
+            } else {
+                references
+                    .iter()
+                    .map(|reference| reference.parse())
+                    .collect::<Result<Vec<Reference>, _>>()?
+            };
+            let mut recorded = 0;
+            for reference in references {
+                let review = match grade {
+                    Some(grade) => log.mark_graded(&day, &reference, grade),
+                    None => log.mark_done(&day, &reference, outcome),
+                };
+                match review {
+                    Some(_) => recorded += 1,
+                    None => eprintln!("warning: {reference} isn't scheduled on {date}"),
+                }
+            }
+            log.append_to(&log_path)?;
+            let start = log.reviews().len() - recorded;
+            print_reviews(&log.reviews()[start..], cli.format)?;
+        }
+        Command::History { reference } => {
+            let reference: Reference = reference.parse()?;
+            let log = load_log(&log_path)?;
+            let history = log.history(&reference).cloned().collect_vec();
+            print_reviews(&history, cli.format)?;
+        }
+        Command::Forecast { from, to, by, svg } => {
+            if to < from {
+                return Err(Error::ReversedRange { from, to });
+            }
+            let forecast = Forecast::new(from, to, &config, for_day);
+            if let Some(path) = svg {
+                std::fs::write(path, forecast.to_svg(by))?;
+            }
+            match cli.format {
+                Format::Json => println!("{}", serde_json::to_string_pretty(&forecast)?),
+                _ => print!("{}", forecast.to_table(by)),
+            }
+        }
+        Command::Adherence => {
+            let log = load_log(&log_path)?;
+            let anchor = plan.anchor().unwrap_or(date);
+            let adherence = Adherence::new(&log, &*scheduler(date), &config, anchor, for_day);
+            match cli.format {
+                Format::Json => println!("{}", serde_json::to_string_pretty(&adherence)?),
+                _ => print!("{adherence}"),
+            }
+        }
+        Command::Today => print_day(date, &for_day(date), cli.format),
+        Command::Day { date } => print_day(date, &for_day(date), cli.format),
+        Command::Range { from, to } => {
+            let mut days = vec![];
+            for day in from.iter_days().take_while(|day| *day <= to) {
+                match cli.format {
+                    Format::Json => days.push(serde_json::to_value(for_day(day))?),
+                    _ => print_day(day, &for_day(day), cli.format),
+                }
+            }
+            if cli.format == Format::Json {
+                println!("{}", serde_json::to_string_pretty(&days)?);
+            }
+        }
+        Command::Booklet { date, output } => {
+            let html = if config.month_mode == MonthMode::Calendar {
+                let month = VersesForACalendarMonth::from_days(date, &config, for_day);
+                let title = format!("Scripture review for {}", date.format("%B %Y"));
+                booklet::render(month.weeks(), &title)
+            } else {
+                let start = month_start(date);
+                let month = VersesForAMonth::from_days(start, for_day);
+                let weeks = month.weeks().iter().map(|week| week.days());
+                booklet::render(weeks, &format!("Scripture review from {start}"))
+            };
+            match output {
+                Some(path) => std::fs::write(path, html)?,
+                None => print!("{html}"),
+            }
+        }
+        Command::Ics { from, to, output } => {
+            let ics = CalendarExport::new(from, to, Utc::now().naive_utc(), for_day).to_ics();
+            match output {
+                Some(path) => std::fs::write(path, ics)?,
+                None => print!("{ics}"),
+            }
+        }
+        Command::Month { date } => {
+            if config.month_mode == MonthMode::Calendar {
+                let month = VersesForACalendarMonth::from_days(date, &config, for_day);
+                match cli.format {
+                    Format::Json => println!("{}", serde_json::to_string_pretty(&month)?),
+                    _ => {
+                        for day in month.days() {
+                            print_day(day.date().unwrap_or(date), day, cli.format);
+                        }
+                    }
+                }
+                return Ok(());
+            }
+            let month = VersesForAMonth::from_days(month_start(date), for_day);
+            if cli.format == Format::Json {
+                println!("{}", serde_json::to_string_pretty(&month)?);
+                return Ok(());
+            }
+            for (w, week) in month.weeks().iter().enumerate() {
+                for (d, day) in week.days().iter().enumerate() {
+                    match cli.format {
+                        Format::Markdown => println!(
+                            "## Week {}, Day {}\n\n{}\n",
+                            w + 1,
+                            d + 1,
+                            render_day(day, cli.format)
+                        ),
+                        Format::Text | Format::Json => println!(
+                            "Week {}, Day {}\n{}\n",
+                            w + 1,
+                            d + 1,
+                            render_day(day, cli.format)
+                        ),
+                    }
+                }
+            }
+        }
+        Command::Generate {
+            passage,
+            start,
+            per_week,
+            skip_between,
+            skip_every,
+            skip,
+            output,
+        } => {
+            let passage = Passage::parse(&passage, cli.versification)?;
+            let mut generator =
+                PlanGenerator::new(start.unwrap_or(date), passage).with_verses_per_week(per_week);
+            for (from, to) in skip_between {
+                generator = generator.with_skip(SkipRule::Between(from, to));
+            }
+            if let Some(n) = skip_every {
+                generator = generator.with_skip(SkipRule::EveryNthWeek(n));
+            }
+            for passage in skip {
+                let passage = Passage::parse(&passage, cli.versification)?;
+                generator = generator.with_skip(SkipRule::Passage(passage));
+            }
+            let list = generator.plan().to_string();
+            match output {
+                Some(path) => std::fs::write(path, list)?,
+                None => print!("{list}"),
+            }
+        }
+        Command::Stats => {
+            let (month, calendar_month);
+            let (days, stats) = match config.month_mode {
+                MonthMode::FourWeek => {
+                    month = VersesForAMonth::from_days(month_start(date), for_day);
+                    let days = month.weeks().iter().flat_map(|week| week.days());
+                    (days.collect_vec(), month.stats())
+                }
+                MonthMode::Calendar => {
+                    calendar_month = VersesForACalendarMonth::from_days(date, &config, for_day);
+                    (
+                        calendar_month.days().iter().collect(),
+                        calendar_month.stats(),
+                    )
+                }
+            };
+            match cli.format {
+                Format::Json => {
+                    let counts = days
+                        .iter()
+                        .map(|day| {
+                            serde_json::json!({
+                                "date": day.date(),
+                                "daily": day.daily().len(),
+                                "weekly": day.weekly().len(),
+                                "monthly": day.monthly().len(),
+                                "yearly": day.yearly().len(),
+                            })
+                        })
+                        .collect_vec();
+                    println!("{}", serde_json::to_string_pretty(&counts)?);
+                }
+                _ => println!("{stats}"),
+            }
+        }
     }
 
     Ok(())
 }
-
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-//
-//     #[test]
-//     fn idk() -> AnyResult<()> {
-//     }
-// }
@@ -0,0 +1,597 @@
+//! Parser for verse list files.
+//!
+//! Each line is `YYYY-MM-DD | Reference`, optionally followed by another `|`
+//! and a comma-separated list of pauses for just that verse, each
+//! `YYYY-MM-DD..YYYY-MM-DD`. Whitespace around the `|` is optional and may
+//! include tabs, `#` starts a comment that runs to the end of the line, and
+//! blank lines are ignored.
+//!
+//! Lines starting with `@` are directives that apply to the whole plan:
+//!
+//! - `@start YYYY-MM-DD` sets the date the 4-week cycle counts from.
+//! - `@pause YYYY-MM-DD..YYYY-MM-DD` pauses every verse. It may be repeated.
+//! - `@month-mode calendar|four-week` picks how days are grouped into
+//!   months. The `--month-mode` flag overrides it.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::reference::ReferenceError;
+use crate::versification::Versification;
+use crate::{Book, Error, FMT, MonthMode, Pause, Plan, Reference, Result, VerseEntry};
+
+/// A range of columns on one line of a verse list. `line` and `column` are
+/// 1-based, `len` is in characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+}
+
+impl Span {
+    /// The span of `text[start..end]`, given as byte offsets.
+    pub(crate) fn from_bytes(line: usize, text: &str, start: usize, end: usize) -> Self {
+        Self {
+            line,
+            column: text[..start].chars().count() + 1,
+            len: text[start..end].chars().count().max(1),
+        }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Byte range of `part` within `text` after trimming whitespace, where `part`
+/// starts at byte `offset` of `text`.
+fn trimmed_range(part: &str, offset: usize) -> (usize, usize) {
+    let start = offset + (part.len() - part.trim_start().len());
+    let end = offset + part.trim_end().len();
+    (start, end.max(start))
+}
+
+/// The end of the longest run of words at the start of `reference` that names
+/// a book, for when [`Reference::split`] can't find the chapter and verse.
+fn book_prefix(reference: &str) -> Option<usize> {
+    reference
+        .char_indices()
+        .filter(|(_, c)| c.is_whitespace())
+        .map(|(i, _)| i)
+        .rfind(|i| Book::parse(reference[..*i].trim()).is_some())
+}
+
+/// A line of a verse list that isn't blank or a comment.
+#[derive(Debug)]
+pub enum Line {
+    Entry(VerseEntry),
+    /// `@start YYYY-MM-DD`
+    Start(NaiveDate),
+    /// `@pause YYYY-MM-DD..YYYY-MM-DD`
+    Pause(Pause),
+    /// `@month-mode four-week` or `@month-mode calendar`
+    MonthMode(MonthMode),
+}
+
+/// Parses one line of a verse list, where `line` is its 1-based line number,
+/// checking that the reference exists in `versification`. Returns `None` for
+/// blank and comment-only lines.
+pub fn parse_line(line: usize, text: &str, versification: Versification) -> Result<Option<Line>> {
+    let content = match text.find('#') {
+        Some(comment) => &text[..comment],
+        None => text,
+    };
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let error = |start, end| (Span::from_bytes(line, text, start, end), text.to_string());
+
+    if content.trim_start().starts_with('@') {
+        let (start, end) = trimmed_range(content, 0);
+        let name_end = content[start..end]
+            .find(char::is_whitespace)
+            .map_or(end, |i| start + i);
+        let (arg_start, arg_end) = trimmed_range(&content[name_end..end], name_end);
+        let arg = &content[arg_start..arg_end];
+        let (arg_span, arg_text) = if arg_start == arg_end {
+            error(start, name_end)
+        } else {
+            error(arg_start, arg_end)
+        };
+        return match &content[start + 1..name_end] {
+            "start" => match NaiveDate::parse_from_str(arg, FMT) {
+                Ok(date) => Ok(Some(Line::Start(date))),
+                Err(_) => Err(Error::InvalidDate {
+                    span: arg_span,
+                    text: arg_text,
+                }),
+            },
+            "pause" => match arg.parse() {
+                Ok(pause) => Ok(Some(Line::Pause(pause))),
+                Err(_) => Err(Error::InvalidPause {
+                    span: arg_span,
+                    text: arg_text,
+                }),
+            },
+            "month-mode" => match arg.parse() {
+                Ok(mode) => Ok(Some(Line::MonthMode(mode))),
+                Err(_) => Err(Error::InvalidMonthMode {
+                    span: arg_span,
+                    text: arg_text,
+                }),
+            },
+            _ => {
+                let (span, text) = error(start, name_end);
+                Err(Error::UnknownDirective { span, text })
+            }
+        };
+    }
+
+    let Some(bar) = content.find('|') else {
+        let (start, end) = trimmed_range(content, 0);
+        let (span, text) = error(start, end);
+        return Err(Error::MissingSeparator { span, text });
+    };
+
+    let (start, end) = trimmed_range(&content[..bar], 0);
+    let Ok(date) = NaiveDate::parse_from_str(&content[start..end], FMT) else {
+        let (span, text) = if start == end {
+            error(bar, bar + 1)
+        } else {
+            error(start, end)
+        };
+        return Err(Error::InvalidDate { span, text });
+    };
+
+    let pauses_bar = content[bar + 1..].find('|').map(|i| bar + 1 + i);
+    let (start, end) = trimmed_range(
+        &content[bar + 1..pauses_bar.unwrap_or(content.len())],
+        bar + 1,
+    );
+    if start == end {
+        let (span, text) = error(bar, bar + 1);
+        return Err(Error::EmptyReference { span, text });
+    }
+    let raw_reference = &content[start..end];
+    let reference = match raw_reference.parse::<Reference>() {
+        Ok(reference) => reference,
+        Err(e) => {
+            let (book, cv) = Reference::split(raw_reference);
+            return Err(match e {
+                ReferenceError::UnknownBook(_) => match book_prefix(raw_reference) {
+                    // `John ab`: the book is fine, what follows it isn't.
+                    Some(book_end) => {
+                        let (cv_start, _) = trimmed_range(&raw_reference[book_end..], book_end);
+                        let (span, text) = error(start + cv_start, end);
+                        Error::InvalidReference { span, text }
+                    }
+                    None => {
+                        let (span, text) = error(start, start + book.len());
+                        Error::UnknownBook { span, text }
+                    }
+                },
+                ReferenceError::InvalidVerse(_) | ReferenceError::NoSuchVerse(_) => {
+                    let (span, text) = if cv.is_empty() {
+                        error(start, end)
+                    } else {
+                        error(end - cv.len(), end)
+                    };
+                    Error::InvalidReference { span, text }
+                }
+            });
+        }
+    };
+    if !versification.contains(&reference) {
+        let (_, cv) = Reference::split(raw_reference);
+        let (span, text) = error(end - cv.len(), end);
+        return Err(Error::NoSuchVerse { span, text });
+    }
+
+    let (start, end) = trimmed_range(content, 0);
+    let span = Span::from_bytes(line, text, start, end);
+    let mut entry = VerseEntry::from_date(date, reference).with_span(span);
+
+    if let Some(pauses_bar) = pauses_bar {
+        let mut offset = pauses_bar + 1;
+        for part in content[pauses_bar + 1..].split(',') {
+            let (start, end) = trimmed_range(part, offset);
+            offset += part.len() + 1;
+            let Ok(pause) = content[start..end].parse() else {
+                let (span, text) = if start == end {
+                    error(start.saturating_sub(1), start)
+                } else {
+                    error(start, end)
+                };
+                return Err(Error::InvalidPause { span, text });
+            };
+            entry = entry.with_pause(pause);
+        }
+    }
+    Ok(Some(Line::Entry(entry)))
+}
+
+/// Parses a verse list along with its directives. Returns the plan made from
+/// the lines that parsed along with an error for every line that didn't.
+pub fn parse_plan(input: &str, versification: Versification) -> (Plan, Vec<Error>) {
+    let mut entries = vec![];
+    let mut start = None;
+    let mut month_mode = None;
+    let mut pauses = vec![];
+    let mut errors = vec![];
+    let mut seen = HashSet::new();
+    for (i, text) in input.lines().enumerate() {
+        match parse_line(i + 1, text, versification) {
+            Ok(None) => {}
+            Ok(Some(Line::Entry(entry))) if seen.contains(entry.reference()) => {
+                let span = entry.span().expect("parsed entries have a span");
+                errors.push(Error::DuplicateEntry {
+                    span,
+                    text: text.to_string(),
+                });
+            }
+            Ok(Some(Line::Entry(entry))) => {
+                seen.insert(*entry.reference());
+                entries.push(entry);
+            }
+            Ok(Some(Line::Start(_))) if start.is_some() => {
+                errors.push(duplicate_directive(i + 1, text));
+            }
+            Ok(Some(Line::MonthMode(_))) if month_mode.is_some() => {
+                errors.push(duplicate_directive(i + 1, text));
+            }
+            Ok(Some(Line::Start(date))) => start = Some(date),
+            Ok(Some(Line::MonthMode(mode))) => month_mode = Some(mode),
+            Ok(Some(Line::Pause(pause))) => pauses.push(pause),
+            Err(error) => errors.push(error),
+        }
+    }
+    let mut plan = Plan::new(entries);
+    if let Some(start) = start {
+        plan = plan.with_start(start);
+    }
+    if let Some(mode) = month_mode {
+        plan = plan.with_month_mode(mode);
+    }
+    for pause in pauses {
+        plan = plan.with_pause(pause);
+    }
+    (plan, errors)
+}
+
+/// The whole of a repeated directive line, as an error.
+fn duplicate_directive(line: usize, text: &str) -> Error {
+    let content = text.split('#').next().unwrap_or_default();
+    let (start, end) = trimmed_range(content, 0);
+    Error::DuplicateDirective {
+        span: Span::from_bytes(line, text, start, end),
+        text: text.to_string(),
+    }
+}
+
+/// Like [`parse_plan`], but fails with every bad line if there are any.
+pub fn parse_plan_strict(input: &str, versification: Versification) -> Result<Plan> {
+    match parse_plan(input, versification) {
+        (plan, errors) if errors.is_empty() => Ok(plan),
+        (_, errors) => Err(Error::Invalid(errors)),
+    }
+}
+
+/// Parses a verse list, ignoring directives. Returns the entries that parsed
+/// along with an error for every line that didn't.
+pub fn parse_entries(input: &str, versification: Versification) -> (Vec<VerseEntry>, Vec<Error>) {
+    let (plan, errors) = parse_plan(input, versification);
+    (plan.into_entries(), errors)
+}
+
+/// Like [`parse_entries`], but fails with every bad line if there are any.
+pub fn parse_entries_strict(input: &str, versification: Versification) -> Result<Vec<VerseEntry>> {
+    match parse_entries(input, versification) {
+        (entries, errors) if errors.is_empty() => Ok(entries),
+        (_, errors) => Err(Error::Invalid(errors)),
+    }
+}
+
+/// Parses a list of dates, one `YYYY-MM-DD` per line, such as the days a plan
+/// was reviewed on. Comments and blank lines are allowed as in a verse list.
+pub fn parse_dates(input: &str) -> (BTreeSet<NaiveDate>, Vec<Error>) {
+    let mut dates = BTreeSet::new();
+    let mut errors = vec![];
+    for (i, text) in input.lines().enumerate() {
+        let content = text.split('#').next().unwrap_or_default();
+        let (start, end) = trimmed_range(content, 0);
+        if start == end {
+            continue;
+        }
+        match NaiveDate::parse_from_str(&content[start..end], FMT) {
+            Ok(date) => {
+                dates.insert(date);
+            }
+            Err(_) => errors.push(Error::InvalidDate {
+                span: Span::from_bytes(i + 1, text, start, end),
+                text: text.to_string(),
+            }),
+        }
+    }
+    (dates, errors)
+}
+
+/// Writes entries back out as a verse list, one per line.
+pub fn write_entries(entries: &[VerseEntry]) -> String {
+    entries.iter().map(|entry| format!("{entry}\n")).collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// Renders an [`Error`] the way a compiler would, pointing at the offending
+/// part of the line:
+///
+/// ```text
+/// error: invalid date, expected YYYY-MM-DD
+///  --> input.txt:2:1
+///   |
+/// 2 | 2025-13-06 | John 1:2
+///   | ^^^^^^^^^^
+/// ```
+pub struct Diagnostic<'a> {
+    pub severity: Severity,
+    pub error: &'a Error,
+    pub source_name: &'a str,
+}
+
+impl<'a> Diagnostic<'a> {
+    pub fn error(error: &'a Error, source_name: &'a str) -> Self {
+        Self {
+            severity: Severity::Error,
+            error,
+            source_name,
+        }
+    }
+
+    pub fn warning(error: &'a Error, source_name: &'a str) -> Self {
+        Self {
+            severity: Severity::Warning,
+            error,
+            source_name,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Error::Invalid(errors) = self.error {
+            for (i, error) in errors.iter().enumerate() {
+                if i > 0 {
+                    writeln!(f)?;
+                }
+                let diagnostic = Diagnostic { error, ..*self };
+                writeln!(f, "{diagnostic}")?;
+            }
+            return write!(f, "{}: {} invalid line(s)", self.severity, errors.len());
+        }
+
+        let (Some(span), Some(text)) = (self.error.span(), self.error.text()) else {
+            return write!(f, "{}: {}", self.severity, self.error);
+        };
+        // Tabs are shown as 4 spaces, so the carets need to account for them.
+        let width = |s: &str| {
+            s.chars()
+                .map(|c| if c == '\t' { 4 } else { 1 })
+                .sum::<usize>()
+        };
+        let line = text.replace('\t', "    ");
+        let before: String = text.chars().take(span.column - 1).collect();
+        let under: String = text.chars().skip(span.column - 1).take(span.len).collect();
+        let gutter = " ".repeat(span.line.to_string().len());
+
+        writeln!(f, "{}: {}", self.severity, self.error.message())?;
+        writeln!(f, "{gutter}--> {}:{span}", self.source_name)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", span.line, line)?;
+        write!(
+            f,
+            "{gutter} | {}{}",
+            " ".repeat(width(&before)),
+            "^".repeat(width(&under).max(1))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(text: &str) -> VerseEntry {
+        match parse_line(1, text, Versification::Kjv) {
+            Ok(Some(Line::Entry(entry))) => entry,
+            other => panic!("expected an entry from `{text}`, got {other:?}"),
+        }
+    }
+
+    fn line_error(text: &str) -> Error {
+        parse_line(1, text, Versification::Kjv).expect_err(text)
+    }
+
+    /// The part of the line an error's span covers.
+    fn underlined(error: &Error) -> String {
+        let span = error.span().unwrap();
+        let text = error.text().unwrap();
+        text.chars().skip(span.column - 1).take(span.len).collect()
+    }
+
+    #[test]
+    fn separators_tolerate_tabs_and_missing_spaces() {
+        let john = Reference::new(Book::John, 1, 1);
+        for text in [
+            "2025-07-06 | John 1:1",
+            "2025-07-06|John 1:1",
+            "2025-07-06\t|\tJohn 1:1",
+            "  2025-07-06 |John 1:1   ",
+            "2025-07-06 | Jn 1:1 # the Word",
+        ] {
+            let entry = entry(text);
+            assert_eq!(entry.date(), NaiveDate::from_ymd_opt(2025, 7, 6).unwrap());
+            assert_eq!(*entry.reference(), john, "{text}");
+        }
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        for text in ["", "   ", "\t", "# a comment", "   # indented comment"] {
+            assert!(parse_line(1, text, Versification::Kjv).unwrap().is_none());
+        }
+        let input = "# plan\n\n2025-07-06 | John 1:1\n\n2025-07-13 | John 1:2 # next\n";
+        let (plan, errors) = parse_plan(input, Versification::Kjv);
+        assert!(errors.is_empty());
+        assert_eq!(plan.entries().len(), 2);
+        assert_eq!(plan.entries()[1].span().unwrap().line, 5);
+    }
+
+    #[test]
+    fn spans_point_at_the_bad_part() {
+        let cases = [
+            ("2025-13-06 | John 1:1", "2025-13-06", 1),
+            ("2025-07-06 John 1:1", "2025-07-06 John 1:1", 1),
+            ("2025-07-06 | ", "|", 12),
+            ("2025-07-06 | Jhon 1:1", "Jhon", 14),
+            ("2025-07-06 | John 1", "1", 19),
+            ("2025-07-06 | John 22:1", "22:1", 19),
+            ("2025-07-06\t| Hezekiah 1:1", "Hezekiah", 14),
+        ];
+        for (text, part, column) in cases {
+            let error = line_error(text);
+            assert_eq!(underlined(&error), part, "{text}");
+            assert_eq!(error.span().unwrap().column, column, "{text}");
+        }
+    }
+
+    #[test]
+    fn bad_chapter_and_verse_after_a_known_book() {
+        for (text, part) in [
+            ("2025-07-06 | John ab", "ab"),
+            ("2025-07-06 | Ps 23:1 é", "23:1 é"),
+            ("2025-07-06 | 1 John x:y", "x:y"),
+        ] {
+            let error = line_error(text);
+            assert!(
+                matches!(error, Error::InvalidReference { .. }),
+                "{text}: {error:?}"
+            );
+            assert_eq!(underlined(&error), part, "{text}");
+        }
+        assert!(matches!(
+            line_error("2025-07-06 | Hezekiah ab"),
+            Error::UnknownBook { .. }
+        ));
+    }
+
+    #[test]
+    fn diagnostics_underline_the_span() {
+        let error = line_error("2025-13-06 | John 1:2");
+        let rendered = Diagnostic::error(&error, "input.txt").to_string();
+        let expected = "\
+error: invalid date, expected YYYY-MM-DD
+ --> input.txt:1:1
+  |
+1 | 2025-13-06 | John 1:2
+  | ^^^^^^^^^^";
+        assert_eq!(rendered, expected);
+
+        // Tabs are widened to 4 columns in both the line and the carets.
+        let error = line_error("2025-07-06\t|\tJhon 1:1");
+        let rendered = Diagnostic::warning(&error, "input.txt").to_string();
+        let mut lines = rendered.lines().skip(3);
+        assert_eq!(lines.next(), Some("1 | 2025-07-06    |    Jhon 1:1"));
+        assert_eq!(lines.next(), Some("  |                    ^^^^"));
+    }
+
+    #[test]
+    fn strict_mode_collects_every_bad_line() {
+        let lines = [
+            "2025-07-06 | John 1:1",
+            "2025-02-30 | John 1:2",
+            "2025-07-06 John 1:3",
+            "2025-07-06 |",
+            "2025-07-06 | John 1:1",
+            "2025-07-06 | Jhon 1:4",
+            "2025-07-06 | John 1",
+            "2025-07-06 | John 1:99",
+            "2025-07-06 | John 1:5 | 2025-07-10",
+            "@stop 2025-07-06",
+            "@start 2025-07-06",
+            "@start 2025-07-13",
+            "2025-07-06 | John 1:6",
+        ];
+        let input = lines.join("\n");
+
+        let Err(Error::Invalid(errors)) = parse_plan_strict(&input, Versification::Kjv) else {
+            panic!("expected every bad line to be reported");
+        };
+        let found: Vec<_> = errors
+            .iter()
+            .map(|error| (error.line().unwrap(), error.text().unwrap()))
+            .collect();
+        let expected: Vec<_> = (2..=10).chain([12]).map(|n| (n, lines[n - 1])).collect();
+        assert_eq!(found, expected);
+
+        let kinds = [
+            matches!(errors[0], Error::InvalidDate { .. }),
+            matches!(errors[1], Error::MissingSeparator { .. }),
+            matches!(errors[2], Error::EmptyReference { .. }),
+            matches!(errors[3], Error::DuplicateEntry { .. }),
+            matches!(errors[4], Error::UnknownBook { .. }),
+            matches!(errors[5], Error::InvalidReference { .. }),
+            matches!(errors[6], Error::NoSuchVerse { .. }),
+            matches!(errors[7], Error::InvalidPause { .. }),
+            matches!(errors[8], Error::UnknownDirective { .. }),
+            matches!(errors[9], Error::DuplicateDirective { .. }),
+        ];
+        assert!(kinds.iter().all(|kind| *kind), "{errors:?}");
+
+        // The lenient parser keeps the good lines and reports the same errors.
+        let (plan, lenient) = parse_plan(&input, Versification::Kjv);
+        assert_eq!(plan.entries().len(), 2);
+        assert_eq!(lenient.len(), errors.len());
+        assert!(parse_entries_strict(lines[0], Versification::Kjv).is_ok());
+    }
+
+    #[test]
+    fn month_mode_directive_is_stored_on_the_plan() {
+        let input = "@month-mode calendar\n2025-07-06 | John 1:1\n";
+        let plan = parse_plan_strict(input, Versification::Kjv).unwrap();
+        assert_eq!(plan.month_mode(), Some(MonthMode::Calendar));
+        assert_eq!(plan.to_string(), input);
+
+        let (plan, _) = parse_plan("2025-07-06 | John 1:1", Versification::Kjv);
+        assert_eq!(plan.month_mode(), None);
+
+        let error = line_error("@month-mode monthly");
+        assert!(matches!(error, Error::InvalidMonthMode { .. }));
+        assert_eq!(underlined(&error), "monthly");
+
+        let input = "@month-mode calendar\n@month-mode four-week\n";
+        let Err(Error::Invalid(errors)) = parse_plan_strict(input, Versification::Kjv) else {
+            panic!("a repeated @month-mode should be rejected");
+        };
+        assert!(matches!(errors[..], [Error::DuplicateDirective { .. }]));
+        assert_eq!(errors[0].line(), Some(2));
+    }
+}
@@ -0,0 +1,105 @@
+//! A verse list together with the settings stored alongside it.
+
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::{FMT, MonthMode, Pause, ScheduledVerses, VerseEntry};
+
+/// The entries of a verse list and its `@` directives.
+#[derive(Debug, Default)]
+pub struct Plan {
+    start: Option<NaiveDate>,
+    month_mode: Option<MonthMode>,
+    pauses: Vec<Pause>,
+    entries: Vec<VerseEntry>,
+}
+
+impl Plan {
+    pub fn new(entries: Vec<VerseEntry>) -> Self {
+        Self {
+            start: None,
+            month_mode: None,
+            pauses: vec![],
+            entries,
+        }
+    }
+
+    /// Counts the 4-week cycle from `start` instead of the earliest entry.
+    pub fn with_start(mut self, start: NaiveDate) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Spreads monthly verses this way unless told otherwise.
+    pub fn with_month_mode(mut self, mode: MonthMode) -> Self {
+        self.month_mode = Some(mode);
+        self
+    }
+
+    /// Stops every verse's schedule during `pause`.
+    pub fn with_pause(mut self, pause: Pause) -> Self {
+        self.pauses.push(pause);
+        self
+    }
+
+    /// The date given with `@start`, if any.
+    pub fn start(&self) -> Option<NaiveDate> {
+        self.start
+    }
+
+    /// The date the 4-week cycle counts from: the `@start` date, or else the
+    /// date of the earliest entry.
+    pub fn anchor(&self) -> Option<NaiveDate> {
+        self.start
+            .or_else(|| self.entries.iter().map(VerseEntry::date).min())
+    }
+
+    /// The month mode given with `@month-mode`, if any. It isn't applied by
+    /// [`Plan::schedule`]; set it on the [`ScheduleConfig`](crate::ScheduleConfig)
+    /// unless something else should take precedence.
+    pub fn month_mode(&self) -> Option<MonthMode> {
+        self.month_mode
+    }
+
+    /// Pauses given with `@pause`, which apply to every verse.
+    pub fn pauses(&self) -> &[Pause] {
+        &self.pauses
+    }
+
+    pub fn entries(&self) -> &[VerseEntry] {
+        &self.entries
+    }
+
+    pub fn into_entries(self) -> Vec<VerseEntry> {
+        self.entries
+    }
+
+    /// The verses to review as of `date`.
+    pub fn schedule(&self, date: NaiveDate) -> ScheduledVerses<'_> {
+        let verses = ScheduledVerses::from_date_with_pauses(date, &self.entries, &self.pauses);
+        match self.start {
+            Some(start) => verses.with_anchor(start),
+            None => verses,
+        }
+    }
+}
+
+impl fmt::Display for Plan {
+    /// Formats the plan as a verse list, directives first.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(start) = self.start {
+            writeln!(f, "@start {}", start.format(FMT))?;
+        }
+        if let Some(mode) = self.month_mode {
+            writeln!(f, "@month-mode {mode}")?;
+        }
+        for pause in &self.pauses {
+            writeln!(f, "@pause {pause}")?;
+        }
+        for entry in &self.entries {
+            writeln!(f, "{entry}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,445 @@
+//! Bible references such as `John 3:16` or `Romans 8:28-30`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+macro_rules! books {
+    ($($book:ident => $name:literal [$($abbr:literal),*],)*) => {
+        /// A book of the 66-book Protestant canon, ordered canonically.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum Book {
+            $($book,)*
+        }
+
+        impl Book {
+            pub const ALL: [Book; 66] = [$(Book::$book,)*];
+
+            /// The canonical English name, e.g. `1 Corinthians`.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Book::$book => $name,)*
+                }
+            }
+
+            /// Common abbreviations, lowercase and without spaces or periods.
+            pub fn abbreviations(self) -> &'static [&'static str] {
+                match self {
+                    $(Book::$book => &[$($abbr),*],)*
+                }
+            }
+        }
+    };
+}
+
+books! {
+    Genesis => "Genesis" ["gen", "ge", "gn"],
+    Exodus => "Exodus" ["exod", "exo", "ex"],
+    Leviticus => "Leviticus" ["lev", "le", "lv"],
+    Numbers => "Numbers" ["num", "nu", "nm", "nb"],
+    Deuteronomy => "Deuteronomy" ["deut", "de", "dt"],
+    Joshua => "Joshua" ["josh", "jos", "jsh"],
+    Judges => "Judges" ["judg", "jdg", "jg", "jdgs"],
+    Ruth => "Ruth" ["rth", "ru"],
+    FirstSamuel => "1 Samuel" ["1sam", "1sa", "1sm", "1s"],
+    SecondSamuel => "2 Samuel" ["2sam", "2sa", "2sm", "2s"],
+    FirstKings => "1 Kings" ["1kgs", "1ki", "1kg", "1k"],
+    SecondKings => "2 Kings" ["2kgs", "2ki", "2kg", "2k"],
+    FirstChronicles => "1 Chronicles" ["1chr", "1chron", "1ch"],
+    SecondChronicles => "2 Chronicles" ["2chr", "2chron", "2ch"],
+    Ezra => "Ezra" ["ezr"],
+    Nehemiah => "Nehemiah" ["neh", "ne"],
+    Esther => "Esther" ["esth", "est", "es"],
+    Job => "Job" ["jb"],
+    Psalms => "Psalms" ["psalm", "ps", "psa", "pss", "psm"],
+    Proverbs => "Proverbs" ["prov", "pro", "prv", "pr"],
+    Ecclesiastes => "Ecclesiastes" ["eccl", "eccles", "ecc", "ec", "qoh"],
+    SongOfSolomon => "Song of Solomon" ["song", "songofsongs", "sos", "sg", "canticles"],
+    Isaiah => "Isaiah" ["isa", "is"],
+    Jeremiah => "Jeremiah" ["jer", "je", "jr"],
+    Lamentations => "Lamentations" ["lam", "la"],
+    Ezekiel => "Ezekiel" ["ezek", "eze", "ezk"],
+    Daniel => "Daniel" ["dan", "da", "dn"],
+    Hosea => "Hosea" ["hos", "ho"],
+    Joel => "Joel" ["jl"],
+    Amos => "Amos" ["am"],
+    Obadiah => "Obadiah" ["obad", "ob"],
+    Jonah => "Jonah" ["jnh"],
+    Micah => "Micah" ["mic", "mc"],
+    Nahum => "Nahum" ["nah", "na"],
+    Habakkuk => "Habakkuk" ["hab", "hb"],
+    Zephaniah => "Zephaniah" ["zeph", "zep", "zp"],
+    Haggai => "Haggai" ["hag", "hg"],
+    Zechariah => "Zechariah" ["zech", "zec", "zc"],
+    Malachi => "Malachi" ["mal", "ml"],
+    Matthew => "Matthew" ["matt", "mt"],
+    Mark => "Mark" ["mrk", "mk", "mr"],
+    Luke => "Luke" ["luk", "lk"],
+    John => "John" ["jn", "jhn"],
+    Acts => "Acts" ["act", "ac"],
+    Romans => "Romans" ["rom", "ro", "rm"],
+    FirstCorinthians => "1 Corinthians" ["1cor", "1co"],
+    SecondCorinthians => "2 Corinthians" ["2cor", "2co"],
+    Galatians => "Galatians" ["gal", "ga"],
+    Ephesians => "Ephesians" ["eph", "ephes"],
+    Philippians => "Philippians" ["phil", "php"],
+    Colossians => "Colossians" ["col"],
+    FirstThessalonians => "1 Thessalonians" ["1thess", "1thes", "1th"],
+    SecondThessalonians => "2 Thessalonians" ["2thess", "2thes", "2th"],
+    FirstTimothy => "1 Timothy" ["1tim", "1ti", "1tm"],
+    SecondTimothy => "2 Timothy" ["2tim", "2ti", "2tm"],
+    Titus => "Titus" ["tit"],
+    Philemon => "Philemon" ["phlm", "philem", "phm"],
+    Hebrews => "Hebrews" ["heb"],
+    James => "James" ["jas", "jm"],
+    FirstPeter => "1 Peter" ["1pet", "1pe", "1pt", "1p"],
+    SecondPeter => "2 Peter" ["2pet", "2pe", "2pt", "2p"],
+    FirstJohn => "1 John" ["1jn", "1jhn", "1jo", "1j"],
+    SecondJohn => "2 John" ["2jn", "2jhn", "2jo", "2j"],
+    ThirdJohn => "3 John" ["3jn", "3jhn", "3jo", "3j"],
+    Jude => "Jude" ["jud", "jd"],
+    Revelation => "Revelation" ["rev", "re", "revelations", "apocalypse"],
+}
+
+/// Lowercases a book name and strips spaces and periods, turning a leading
+/// `I`/`II`/`III` or `First`/`Second`/`Third` into a digit.
+fn normalize(name: &str) -> String {
+    let name = name.trim().to_lowercase().replace('.', " ");
+    let mut words = name.split_whitespace().peekable();
+    let mut normalized = String::new();
+    if let Some(first) = words.peek() {
+        let number = match *first {
+            "i" | "first" | "1st" => Some("1"),
+            "ii" | "second" | "2nd" => Some("2"),
+            "iii" | "third" | "3rd" => Some("3"),
+            _ => None,
+        };
+        if let Some(number) = number {
+            normalized.push_str(number);
+            words.next();
+        }
+    }
+    normalized.extend(words);
+    normalized
+}
+
+impl Book {
+    /// Parses a full book name or a common abbreviation, ignoring case,
+    /// spacing and periods.
+    pub fn parse(name: &str) -> Option<Book> {
+        let name = normalize(name);
+        Book::ALL.into_iter().find(|book| {
+            normalize(book.name()) == name || book.abbreviations().contains(&name.as_str())
+        })
+    }
+}
+
+impl fmt::Display for Book {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReferenceError {
+    /// The text before the chapter isn't a known book or abbreviation.
+    UnknownBook(String),
+    /// The chapter and verse aren't of the form `3:16` or `3:16-18`.
+    InvalidVerse(String),
+    /// The chapter or verse is past the end of the book or chapter.
+    NoSuchVerse(String),
+}
+
+impl fmt::Display for ReferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReferenceError::UnknownBook(book) => write!(f, "unknown book `{book}`"),
+            ReferenceError::InvalidVerse(verse) => {
+                write!(
+                    f,
+                    "expected `chapter:verse` or `chapter:verse-verse`, found `{verse}`"
+                )
+            }
+            ReferenceError::NoSuchVerse(reference) => write!(f, "`{reference}` doesn't exist"),
+        }
+    }
+}
+
+impl std::error::Error for ReferenceError {}
+
+/// A single verse or a range of verses within one chapter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Reference {
+    book: Book,
+    chapter: u16,
+    verse: u16,
+    end_verse: Option<u16>,
+}
+
+impl Reference {
+    pub fn new(book: Book, chapter: u16, verse: u16) -> Self {
+        Self {
+            book,
+            chapter,
+            verse,
+            end_verse: None,
+        }
+    }
+
+    /// A range from `verse` through `end_verse`, inclusive.
+    pub fn range(book: Book, chapter: u16, verse: u16, end_verse: u16) -> Self {
+        let end_verse = (end_verse > verse).then_some(end_verse);
+        Self {
+            book,
+            chapter,
+            verse,
+            end_verse,
+        }
+    }
+
+    pub fn book(&self) -> Book {
+        self.book
+    }
+
+    pub fn chapter(&self) -> u16 {
+        self.chapter
+    }
+
+    pub fn verse(&self) -> u16 {
+        self.verse
+    }
+
+    /// The last verse of a range, or `None` for a single verse.
+    pub fn end_verse(&self) -> Option<u16> {
+        self.end_verse
+    }
+
+    /// How many verses this reference covers, 1 for a single verse.
+    pub fn verse_count(&self) -> u16 {
+        self.end_verse.map_or(1, |end| end - self.verse + 1)
+    }
+
+    /// Splits `1 John 4:8` into `("1 John", "4:8")` as byte offsets, so the
+    /// parser can point at whichever half is wrong.
+    pub(crate) fn split(text: &str) -> (&str, &str) {
+        let text = text.trim();
+        let cv_start = text
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| {
+                c.is_ascii_digit() || matches!(c, ':' | '-' | '–') || c.is_whitespace()
+            })
+            .last()
+            .map(|(i, _)| i)
+            .unwrap_or(text.len());
+        // Keep a leading book number like the `1` in `1 John` with the book.
+        let cv_start = match text[..cv_start].trim_end() {
+            "" => text.find(char::is_whitespace).unwrap_or(text.len()),
+            _ => cv_start,
+        };
+        (text[..cv_start].trim_end(), text[cv_start..].trim_start())
+    }
+}
+
+impl FromStr for Reference {
+    type Err = ReferenceError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (book_name, cv) = Reference::split(text);
+        let book = Book::parse(book_name)
+            .ok_or_else(|| ReferenceError::UnknownBook(book_name.to_string()))?;
+
+        let invalid = || ReferenceError::InvalidVerse(cv.to_string());
+        let cv: String = cv.chars().filter(|c| !c.is_whitespace()).collect();
+        let (start, end) = match cv.split_once(['-', '–']) {
+            Some((start, end)) => (start, Some(end)),
+            None => (cv.as_str(), None),
+        };
+        let (chapter, verse) = start.split_once(':').ok_or_else(invalid)?;
+        let parse = |n: &str| n.parse::<u16>().map_err(|_| invalid());
+        let chapter = parse(chapter)?;
+        let verse = parse(verse)?;
+        let end_verse = match end.map(|end| end.split_once(':').unwrap_or(("", end))) {
+            None => None,
+            Some(("", end_verse)) => Some(parse(end_verse)?),
+            Some((end_chapter, end_verse)) if parse(end_chapter)? == chapter => {
+                Some(parse(end_verse)?)
+            }
+            // `3:16-4:2` spans chapters, which isn't supported.
+            Some(_) => return Err(invalid()),
+        };
+        if chapter == 0 || verse == 0 || end_verse.is_some_and(|end| end < verse) {
+            return Err(invalid());
+        }
+
+        Ok(match end_verse {
+            Some(end_verse) => Reference::range(book, chapter, verse, end_verse),
+            None => Reference::new(book, chapter, verse),
+        })
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // A single psalm reads "Psalm 23", not "Psalms 23".
+        let book = match self.book {
+            Book::Psalms => "Psalm",
+            book => book.name(),
+        };
+        write!(f, "{} {}:{}", book, self.chapter, self.verse)?;
+        if let Some(end_verse) = self.end_verse {
+            write!(f, "-{end_verse}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Reference {
+    /// Written in its canonical form, e.g. `"Romans 8:28-30"`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Reference {
+    /// Read from any form [`FromStr`] accepts.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Reference {
+        text.parse()
+            .unwrap_or_else(|e| panic!("`{text}` should parse: {e}"))
+    }
+
+    #[test]
+    fn every_name_and_abbreviation_finds_its_book() {
+        for book in Book::ALL {
+            assert_eq!(Book::parse(book.name()), Some(book), "{book}");
+            assert_eq!(Book::parse(&book.name().to_uppercase()), Some(book));
+            for abbreviation in book.abbreviations() {
+                assert_eq!(Book::parse(abbreviation), Some(book), "{abbreviation}");
+            }
+        }
+        assert_eq!(Book::parse("Rom."), Some(Book::Romans));
+        assert_eq!(Book::parse("song of songs"), Some(Book::SongOfSolomon));
+        assert_eq!(Book::parse("Hezekiah"), None);
+        assert_eq!(Book::parse(""), None);
+    }
+
+    #[test]
+    fn no_abbreviation_is_shared_between_books() {
+        let mut seen = std::collections::HashMap::new();
+        for book in Book::ALL {
+            for abbreviation in book.abbreviations() {
+                if let Some(other) = seen.insert(*abbreviation, book) {
+                    panic!("`{abbreviation}` is used by both {other} and {book}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn numbered_books_accept_roman_numerals_and_words() {
+        for name in [
+            "1 John",
+            "1John",
+            "I John",
+            "First John",
+            "1st John",
+            "1 Jn",
+            "I Jn.",
+        ] {
+            assert_eq!(Book::parse(name), Some(Book::FirstJohn), "{name}");
+        }
+        for name in ["II Kings", "Second Kings", "2nd Kgs"] {
+            assert_eq!(Book::parse(name), Some(Book::SecondKings), "{name}");
+        }
+        for name in ["III John", "Third John", "3rd Jn"] {
+            assert_eq!(Book::parse(name), Some(Book::ThirdJohn), "{name}");
+        }
+        assert_eq!(
+            parse("I Cor 13:4"),
+            Reference::new(Book::FirstCorinthians, 13, 4)
+        );
+        // Only a leading word is a number, so `Isaiah` isn't `1 saiah`.
+        assert_eq!(Book::parse("Is"), Some(Book::Isaiah));
+    }
+
+    #[test]
+    fn psalms_display_as_a_single_psalm() {
+        assert_eq!(parse("Psalms 23:1").to_string(), "Psalm 23:1");
+        assert_eq!(parse("Ps 119:9-11").to_string(), "Psalm 119:9-11");
+        assert_eq!(Book::Psalms.to_string(), "Psalms");
+        assert_eq!(parse("1 cor 13:4-7").to_string(), "1 Corinthians 13:4-7");
+    }
+
+    #[test]
+    fn ranges_within_a_chapter() {
+        let range = Reference::range(Book::Romans, 8, 28, 30);
+        assert_eq!(parse("Romans 8:28-30"), range);
+        assert_eq!(parse("Romans 8:28–30"), range);
+        assert_eq!(parse("Romans 8:28 – 30"), range);
+        assert_eq!(parse("Romans 8:28-8:30"), range);
+        assert_eq!(range.verse_count(), 3);
+        assert_eq!(parse("Romans 8:28-28"), Reference::new(Book::Romans, 8, 28));
+    }
+
+    #[test]
+    fn rejects_ranges_across_chapters_and_backwards() {
+        for text in [
+            "John 3:16-4:2",
+            "John 3:16–4:2",
+            "John 3:16-3",
+            "John 0:1",
+            "John 3:0",
+        ] {
+            assert!(
+                matches!(
+                    text.parse::<Reference>(),
+                    Err(ReferenceError::InvalidVerse(_))
+                ),
+                "{text}"
+            );
+        }
+        assert_eq!(
+            "Hezekiah 1:1".parse::<Reference>(),
+            Err(ReferenceError::UnknownBook("Hezekiah".to_string()))
+        );
+    }
+
+    #[test]
+    fn references_sort_canonically() {
+        let mut references = [
+            parse("Revelation 1:1"),
+            parse("John 3:16"),
+            parse("Genesis 50:1"),
+            parse("John 3:2"),
+            parse("John 1:14"),
+            parse("Genesis 1:1"),
+            parse("John 3:2-4"),
+        ]
+        .to_vec();
+        references.sort();
+        let sorted: Vec<_> = references.iter().map(|r| r.to_string()).collect();
+        assert_eq!(
+            sorted,
+            [
+                "Genesis 1:1",
+                "Genesis 50:1",
+                "John 1:14",
+                "John 3:2",
+                "John 3:2-4",
+                "John 3:16",
+                "Revelation 1:1",
+            ]
+        );
+    }
+}
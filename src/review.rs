@@ -0,0 +1,528 @@
+//! Log of the reviews that were actually done, kept as a JSON-lines file next
+//! to the verse list. Each line is one review:
+//!
+//! ```text
+//! {"date":"2025-07-06","reference":"John 1:1","phase":"daily","outcome":"reviewed"}
+//! {"date":"2025-07-07","reference":"John 1:1","phase":"daily","outcome":"reviewed","grade":"easy"}
+//! ```
+//!
+//! New reviews are only ever appended, so the file doubles as a history that
+//! can be kept under version control or edited by hand.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Frequency, Reference, Result, ScheduleConfig, Span, VersesForADay};
+
+/// Whether a scheduled review was done.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Reviewed,
+    Skipped,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Reviewed => f.write_str("reviewed"),
+            Outcome::Skipped => f.write_str("skipped"),
+        }
+    }
+}
+
+/// How well a verse was recalled when it was reviewed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Grade {
+    /// Not recalled.
+    Again,
+    /// Recalled with mistakes or prompting.
+    Hard,
+    Good,
+    /// Recalled without effort.
+    Easy,
+}
+
+impl Grade {
+    /// How many half-days this grade moves a verse along its schedule when
+    /// given in a phase reviewed every `interval` days: `again` holds it back
+    /// a whole interval, as if the review hadn't happened, `hard` half of
+    /// one, and `easy` moves it on an extra interval. Half-days keep a `hard`
+    /// in the daily phase from counting as much as an `again`.
+    pub fn half_days(self, interval: i64) -> i64 {
+        match self {
+            Grade::Again => -2 * interval,
+            Grade::Hard => -interval,
+            Grade::Good => 0,
+            Grade::Easy => 2 * interval,
+        }
+    }
+}
+
+impl FromStr for Grade {
+    type Err = String;
+
+    fn from_str(name: &str) -> std::result::Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "again" => Ok(Grade::Again),
+            "hard" => Ok(Grade::Hard),
+            "good" => Ok(Grade::Good),
+            "easy" => Ok(Grade::Easy),
+            _ => Err(format!(
+                "unknown grade `{name}`, expected again, hard, good or easy"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Grade::Again => f.write_str("again"),
+            Grade::Hard => f.write_str("hard"),
+            Grade::Good => f.write_str("good"),
+            Grade::Easy => f.write_str("easy"),
+        }
+    }
+}
+
+/// Days a verse's grades move it along its schedule in each phase, positive
+/// when it's ahead. See [`Adjustment::of`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Adjustment {
+    pub daily: i64,
+    pub weekly: i64,
+}
+
+impl Adjustment {
+    /// How far `grades`, oldest first, move a verse along its phases.
+    ///
+    /// Each grade counts [`Grade::half_days`] of the interval of the phase it
+    /// was given in, and the total is rounded toward zero to whole days. Each
+    /// phase can be stretched to at most twice its length or cut to half of
+    /// it, and a phase with no length isn't adjusted.
+    pub fn of(grades: &[(Frequency, Grade)], config: &ScheduleConfig) -> Self {
+        // In half-days, so the bounds are twice and once the phase length.
+        let daily_len = 7 * config.daily_weeks.max(0);
+        let weekly_len = 7 * config.weekly_weeks.max(0);
+        let (mut daily, mut weekly) = (0, 0);
+        for (phase, grade) in grades {
+            match phase {
+                Frequency::Daily => {
+                    daily = (daily + grade.half_days(1)).clamp(-2 * daily_len, daily_len);
+                }
+                Frequency::Weekly => {
+                    weekly = (weekly + grade.half_days(7)).clamp(-2 * weekly_len, weekly_len);
+                }
+                _ => {}
+            }
+        }
+        Self {
+            daily: daily / 2,
+            weekly: weekly / 2,
+        }
+    }
+
+    /// `days_in` moved by this adjustment. Grades given in the weekly phase
+    /// hold a verse back within it but never return it to the daily phase.
+    pub fn apply(&self, days_in: i64, config: &ScheduleConfig) -> i64 {
+        let daily_end = 7 * config.daily_weeks;
+        let days_in = days_in + self.daily;
+        if days_in < daily_end {
+            return days_in;
+        }
+        (days_in + self.weekly).max(daily_end)
+    }
+}
+
+/// One verse reviewed (or skipped) on one day.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Review {
+    date: NaiveDate,
+    reference: Reference,
+    phase: Frequency,
+    outcome: Outcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    grade: Option<Grade>,
+}
+
+impl Review {
+    pub fn new(date: NaiveDate, reference: Reference, phase: Frequency, outcome: Outcome) -> Self {
+        Self {
+            date,
+            reference,
+            phase,
+            outcome,
+            grade: None,
+        }
+    }
+
+    pub fn with_grade(mut self, grade: Grade) -> Self {
+        self.grade = Some(grade);
+        self
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    pub fn reference(&self) -> &Reference {
+        &self.reference
+    }
+
+    /// Which list the verse was in when it was reviewed.
+    pub fn phase(&self) -> Frequency {
+        self.phase
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        self.outcome
+    }
+
+    /// How well the verse was recalled, if that was recorded.
+    pub fn grade(&self) -> Option<Grade> {
+        self.grade
+    }
+}
+
+/// Every review recorded so far, oldest first, along with any recorded since
+/// the log was read that haven't been appended to the file yet.
+#[derive(Clone, Debug, Default)]
+pub struct ReviewLog {
+    reviews: Vec<Review>,
+    saved: usize,
+}
+
+impl ReviewLog {
+    /// Where the log for the verse list at `input` is kept: alongside it, so
+    /// `input.txt` is logged to `input.reviews.jsonl`.
+    pub fn path_for(input: &Path) -> PathBuf {
+        input.with_extension("reviews.jsonl")
+    }
+
+    /// Parses a log, skipping blank lines. Lines that aren't a valid review are
+    /// returned as errors and left out.
+    pub fn parse(input: &str) -> (Self, Vec<Error>) {
+        let mut reviews = vec![];
+        let mut errors = vec![];
+        for (i, text) in input.lines().enumerate() {
+            let trimmed = text.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            match serde_json::from_str(trimmed) {
+                Ok(review) => reviews.push(review),
+                Err(_) => {
+                    let start = text.len() - text.trim_start().len();
+                    errors.push(Error::InvalidReview {
+                        span: Span::from_bytes(i + 1, text, start, start + trimmed.len()),
+                        text: text.to_string(),
+                    });
+                }
+            }
+        }
+        let saved = reviews.len();
+        (Self { reviews, saved }, errors)
+    }
+
+    pub fn reviews(&self) -> &[Review] {
+        &self.reviews
+    }
+
+    pub fn record(&mut self, review: Review) {
+        self.reviews.push(review);
+    }
+
+    /// Records `reference` as reviewed (or skipped) in the phase `day` assigns
+    /// it to. Returns `None`, recording nothing, if it isn't one of the day's
+    /// verses or the day has no date.
+    pub fn mark_done(
+        &mut self,
+        day: &VersesForADay,
+        reference: &Reference,
+        outcome: Outcome,
+    ) -> Option<&Review> {
+        self.mark(day, reference, outcome, None)
+    }
+
+    /// Like [`ReviewLog::mark_done`], recording `reference` as reviewed with
+    /// `grade`.
+    pub fn mark_graded(
+        &mut self,
+        day: &VersesForADay,
+        reference: &Reference,
+        grade: Grade,
+    ) -> Option<&Review> {
+        self.mark(day, reference, Outcome::Reviewed, Some(grade))
+    }
+
+    fn mark(
+        &mut self,
+        day: &VersesForADay,
+        reference: &Reference,
+        outcome: Outcome,
+        grade: Option<Grade>,
+    ) -> Option<&Review> {
+        let date = day.date()?;
+        let (phase, verse) = day
+            .items()
+            .find(|(_, verse)| verse.reference() == reference)?;
+        let review = Review::new(date, *verse.reference(), phase, outcome);
+        self.record(Review { grade, ..review });
+        self.reviews.last()
+    }
+
+    /// Records every verse `day` assigns, returning the new reviews.
+    pub fn mark_all_done(&mut self, day: &VersesForADay, outcome: Outcome) -> &[Review] {
+        let start = self.reviews.len();
+        if let Some(date) = day.date() {
+            for (phase, verse) in day.items() {
+                self.record(Review::new(date, *verse.reference(), phase, outcome));
+            }
+        }
+        &self.reviews[start..]
+    }
+
+    /// Every review of `reference`, oldest first.
+    pub fn history<'a>(&'a self, reference: &'a Reference) -> impl Iterator<Item = &'a Review> {
+        self.reviews
+            .iter()
+            .filter(move |review| review.reference == *reference)
+    }
+
+    /// How far the grades given to `reference` before `date` move it along
+    /// its daily and weekly phases. See [`Adjustment::of`].
+    pub fn adjustment(
+        &self,
+        reference: &Reference,
+        date: NaiveDate,
+        config: &ScheduleConfig,
+    ) -> Adjustment {
+        Adjustment::of(&self.grades(reference, date), config)
+    }
+
+    /// The phase and grade of each graded review of `reference` before
+    /// `date`, oldest first.
+    pub fn grades(&self, reference: &Reference, date: NaiveDate) -> Vec<(Frequency, Grade)> {
+        self.history(reference)
+            .filter(|review| review.date < date)
+            .filter_map(|review| Some((review.phase, review.grade?)))
+            .collect()
+    }
+
+    /// Days with at least one verse reviewed, as used by
+    /// [`CatchUp`](crate::CatchUp).
+    pub fn completed_days(&self) -> BTreeSet<NaiveDate> {
+        self.reviews
+            .iter()
+            .filter(|review| review.outcome == Outcome::Reviewed)
+            .map(|review| review.date)
+            .collect()
+    }
+
+    /// Appends the reviews recorded since the log was read to the file at
+    /// `path`, creating it if needed.
+    pub fn append_to(&mut self, path: &Path) -> Result<()> {
+        let mut lines = String::new();
+        for review in &self.reviews[self.saved..] {
+            lines.push_str(&serde_json::to_string(review)?);
+            lines.push('\n');
+        }
+        if !lines.is_empty() {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            file.write_all(lines.as_bytes())?;
+        }
+        self.saved = self.reviews.len();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::Days;
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::{Book, ScheduledVerses, VerseEntry};
+
+    #[test]
+    fn adjustment_stays_in_bounds_for_any_phase_length() {
+        let start = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let reference = Reference::new(Book::John, 1, 1);
+        let mut log = ReviewLog::default();
+        for (day, phase, grade) in [
+            (0, Frequency::Daily, Grade::Easy),
+            (1, Frequency::Daily, Grade::Again),
+            (2, Frequency::Weekly, Grade::Again),
+            (9, Frequency::Weekly, Grade::Easy),
+        ] {
+            let review = Review::new(start + Days::new(day), reference, phase, Outcome::Reviewed);
+            log.record(review.with_grade(grade));
+        }
+        let date = start + Days::new(30);
+
+        for weeks in [-3, 0, 1, 7] {
+            let config = ScheduleConfig {
+                daily_weeks: weeks,
+                weekly_weeks: weeks,
+                ..ScheduleConfig::default()
+            };
+            let adjustment = log.adjustment(&reference, date, &config);
+            let (daily, weekly) = (7 * weeks.max(0), 7 * weeks.max(0));
+            assert!((-daily..=daily / 2).contains(&adjustment.daily), "{weeks}");
+            assert!(
+                (-weekly..=weekly / 2).contains(&adjustment.weekly),
+                "{weeks}"
+            );
+        }
+        let adjustment = log.adjustment(&reference, date, &ScheduleConfig::default());
+        assert_eq!(
+            adjustment,
+            Adjustment {
+                daily: 0,
+                weekly: 0
+            }
+        );
+    }
+
+    #[test]
+    fn hard_in_the_daily_phase_costs_half_a_day() {
+        let start = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let reference = Reference::new(Book::John, 1, 1);
+        let graded = |grades: &[Grade]| {
+            let mut log = ReviewLog::default();
+            for (day, grade) in grades.iter().enumerate() {
+                let date = start + Days::new(day as u64);
+                let review = Review::new(date, reference, Frequency::Daily, Outcome::Reviewed);
+                log.record(review.with_grade(*grade));
+            }
+            let date = start + Days::new(grades.len() as u64);
+            log.adjustment(&reference, date, &ScheduleConfig::default())
+                .daily
+        };
+
+        assert_eq!(graded(&[Grade::Again]), -1);
+        assert_eq!(graded(&[Grade::Hard]), 0);
+        assert_eq!(graded(&[Grade::Hard, Grade::Hard]), -1);
+        assert_eq!(graded(&[Grade::Hard, Grade::Hard, Grade::Hard]), -1);
+        assert_eq!(graded(&[Grade::Hard, Grade::Easy]), 0);
+        assert_eq!(graded(&[Grade::Hard; 4]), -2);
+    }
+
+    #[test]
+    fn grades_follow_the_config_whether_set_before_or_after() {
+        let start = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let reference = Reference::new(Book::John, 1, 1);
+        let entries = [VerseEntry::from_date(start, reference)];
+        let mut log = ReviewLog::default();
+        for day in 0..10 {
+            let review = Review::new(
+                start + Days::new(day),
+                reference,
+                Frequency::Daily,
+                Outcome::Reviewed,
+            );
+            log.record(review.with_grade(Grade::Easy));
+        }
+        let config = ScheduleConfig {
+            daily_weeks: 2,
+            ..ScheduleConfig::default()
+        };
+        let date = start + Days::new(10);
+        let days_in = |verses: ScheduledVerses| verses.verses()[0].days_in();
+
+        // The short daily phase caps the bonus at 7 days either way round.
+        let before = ScheduledVerses::from_date(date, &entries)
+            .with_grades(&log)
+            .with_config(config);
+        let after = ScheduledVerses::from_date(date, &entries)
+            .with_config(config)
+            .with_grades(&log);
+        assert_eq!(days_in(before), 17);
+        assert_eq!(days_in(after), 17);
+    }
+
+    #[test]
+    fn review_log_round_trips_through_its_text() {
+        let today = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        // Well into its monthly phase, so not reviewed every day.
+        let monthly = Reference::new(Book::John, 1, 1);
+        let mut entries = vec![VerseEntry::from_date(today - Days::new(7 * 40), monthly)];
+        let new = Reference::new(Book::Romans, 8, 28);
+        entries.push(VerseEntry::from_date(today, new));
+        let verses = ScheduledVerses::from_date(today, &entries);
+        let day = verses.for_today();
+
+        let mut log = ReviewLog::default();
+        let review = log.mark_done(&day, &new, Outcome::Reviewed).cloned();
+        assert_eq!(review.map(|r| r.phase()), Some(Frequency::Daily));
+        let unscheduled = Reference::new(Book::Jude, 1, 24);
+        assert!(
+            log.mark_done(&day, &unscheduled, Outcome::Reviewed)
+                .is_none()
+        );
+        log.mark_all_done(&day, Outcome::Skipped);
+
+        let text = log
+            .reviews()
+            .iter()
+            .map(|review| serde_json::to_string(review).unwrap() + "\n")
+            .collect::<String>();
+        let (parsed, errors) = ReviewLog::parse(&format!("{text}\nnot json\n"));
+        assert_eq!(parsed.reviews(), log.reviews());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line(), Some(log.reviews().len() + 2));
+
+        let history = parsed.history(&new).map(|r| r.outcome()).collect_vec();
+        assert_eq!(history, [Outcome::Reviewed, Outcome::Skipped]);
+        assert_eq!(parsed.completed_days(), BTreeSet::from([today]));
+    }
+
+    #[test]
+    fn grades_stretch_or_shorten_the_daily_phase() {
+        let start = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let easy = Reference::new(Book::John, 1, 1);
+        let again = Reference::new(Book::John, 1, 2);
+        let entries = [easy, again].map(|reference| VerseEntry::from_date(start, reference));
+
+        let mut log = ReviewLog::default();
+        let mut last_daily = HashMap::new();
+        let mut first_weekly = HashMap::new();
+        for day in 0..120 {
+            let date = start + Days::new(day);
+            let verses = ScheduledVerses::from_date(date, &entries).with_grades(&log);
+            let today = verses.for_today();
+            for verse in today.daily() {
+                last_daily.insert(*verse.reference(), day);
+            }
+            for verse in today.weekly() {
+                first_weekly.entry(*verse.reference()).or_insert(day);
+            }
+            for reference in [easy, again] {
+                let grade = if reference == easy {
+                    Grade::Easy
+                } else {
+                    Grade::Again
+                };
+                log.mark_graded(&today, &reference, grade);
+            }
+        }
+
+        // Easy counts each day twice, up to half the 49-day phase; again
+        // doesn't count it at all, up to doubling it.
+        assert_eq!(last_daily[&easy], 24);
+        assert_eq!(last_daily[&again], 97);
+        assert!((25..32).contains(&first_weekly[&easy]));
+        assert!(first_weekly[&again] >= 98);
+    }
+}
@@ -0,0 +1,291 @@
+//! Spaced-repetition scheduling from the review log, as an alternative to
+//! Frost's fixed ladder. Each verse is due again a number of days after its
+//! last review that depends on how it was graded, using either
+//! [SM-2](https://super-memory.com/english/ol/sm2.htm) or
+//! [FSRS](https://github.com/open-spaced-repetition/fsrs4anki/wiki/The-Algorithm).
+//!
+//! Reviews logged without a grade count as `good`, and skipped ones aren't
+//! counted at all.
+
+use chrono::{Days, NaiveDate};
+
+use crate::review::{Grade, Outcome, ReviewLog};
+use crate::{Frequency, Pause, Scheduler, Verse, VerseEntry, VersesForADay};
+
+/// Updates a verse's memory state after each review.
+pub trait Algorithm: Default {
+    /// Takes a review graded `grade`, given `elapsed` days after the previous
+    /// one (0 for the first), and returns how many days to wait until the
+    /// next.
+    fn review(&mut self, grade: Grade, elapsed: i64) -> i64;
+}
+
+/// SuperMemory's SM-2: intervals of 1 and 6 days, then growing by an ease
+/// factor that falls with every hard review and rises with every easy one.
+#[derive(Clone, Copy, Debug)]
+pub struct Sm2 {
+    repetitions: u32,
+    ease: f64,
+    interval: i64,
+}
+
+impl Default for Sm2 {
+    fn default() -> Self {
+        Self {
+            repetitions: 0,
+            ease: 2.5,
+            interval: 0,
+        }
+    }
+}
+
+impl Algorithm for Sm2 {
+    fn review(&mut self, grade: Grade, _elapsed: i64) -> i64 {
+        // SM-2 grades recall from 0 to 5, 3 being the lowest passing grade.
+        let quality = match grade {
+            Grade::Again => 1.0,
+            Grade::Hard => 3.0,
+            Grade::Good => 4.0,
+            Grade::Easy => 5.0,
+        };
+        if grade == Grade::Again {
+            self.repetitions = 0;
+            self.interval = 1;
+        } else {
+            self.interval = match self.repetitions {
+                0 => 1,
+                1 => 6,
+                _ => (self.interval as f64 * self.ease).round() as i64,
+            };
+            self.repetitions += 1;
+        }
+        let miss = 5.0 - quality;
+        self.ease = (self.ease + 0.1 - miss * (0.08 + miss * 0.02)).max(1.3);
+        self.interval
+    }
+}
+
+/// FSRS-4.5 default parameters.
+const W: [f64; 17] = [
+    0.4872, 1.4003, 3.7145, 13.8206, 5.1618, 1.2298, 0.8975, 0.031, 1.6474, 0.1367, 1.0461, 2.1072,
+    0.0793, 0.3246, 1.587, 0.2272, 2.8755,
+];
+const DECAY: f64 = -0.5;
+const FACTOR: f64 = 19.0 / 81.0;
+
+/// The Free Spaced Repetition Scheduler (FSRS-4.5, default parameters). It
+/// tracks how stable each verse is in memory and how difficult it is, and
+/// schedules the next review for when the chance of recalling it has fallen
+/// to the target retention.
+#[derive(Clone, Copy, Debug)]
+pub struct Fsrs {
+    /// Stability in days and difficulty from 1 to 10, once reviewed.
+    memory: Option<(f64, f64)>,
+    retention: f64,
+}
+
+impl Fsrs {
+    /// Schedules reviews for when recall is expected to have fallen to
+    /// `retention`, e.g. 0.9 for 90%.
+    pub fn with_retention(retention: f64) -> Self {
+        Self {
+            memory: None,
+            retention,
+        }
+    }
+
+    fn initial_difficulty(rating: f64) -> f64 {
+        (W[4] - (rating - 3.0) * W[5]).clamp(1.0, 10.0)
+    }
+}
+
+impl Default for Fsrs {
+    fn default() -> Self {
+        Self::with_retention(0.9)
+    }
+}
+
+impl Algorithm for Fsrs {
+    fn review(&mut self, grade: Grade, elapsed: i64) -> i64 {
+        let rating = match grade {
+            Grade::Again => 1.0,
+            Grade::Hard => 2.0,
+            Grade::Good => 3.0,
+            Grade::Easy => 4.0,
+        };
+        let (stability, difficulty) = match self.memory {
+            None => (W[rating as usize - 1], Self::initial_difficulty(rating)),
+            Some((s, d)) => {
+                let retrievability = (1.0 + FACTOR * elapsed as f64 / s).powf(DECAY);
+                let stability = if grade == Grade::Again {
+                    W[11]
+                        * d.powf(-W[12])
+                        * ((s + 1.0).powf(W[13]) - 1.0)
+                        * (W[14] * (1.0 - retrievability)).exp()
+                } else {
+                    let bonus = match grade {
+                        Grade::Hard => W[15],
+                        Grade::Easy => W[16],
+                        _ => 1.0,
+                    };
+                    s * (1.0
+                        + W[8].exp()
+                            * (11.0 - d)
+                            * s.powf(-W[9])
+                            * ((W[10] * (1.0 - retrievability)).exp() - 1.0)
+                            * bonus)
+                };
+                let difficulty = d - W[6] * (rating - 3.0);
+                let difficulty = W[7] * Self::initial_difficulty(3.0) + (1.0 - W[7]) * difficulty;
+                (stability, difficulty.clamp(1.0, 10.0))
+            }
+        };
+        self.memory = Some((stability, difficulty));
+        let interval = stability / FACTOR * (self.retention.powf(1.0 / DECAY) - 1.0);
+        (interval.round() as i64).max(1)
+    }
+}
+
+/// The verses due on a day by a spaced-repetition [`Algorithm`].
+///
+/// Due verses are listed by their current interval: under a week as daily,
+/// under four weeks as weekly, under a year as monthly and beyond that as
+/// yearly. Verses that haven't been reviewed yet are daily from their start
+/// date, and overdue verses stay listed until they're reviewed.
+#[derive(Clone, Debug)]
+pub struct SpacedRepetition<'a> {
+    date: NaiveDate,
+    /// Each started verse with when it's next due and its current interval.
+    verses: Vec<(NaiveDate, i64, Verse<'a>)>,
+}
+
+impl<'a> SpacedRepetition<'a> {
+    /// Replays `log`'s reviews from before `date` through `A`. Verses that
+    /// haven't started yet or are paused on `date` aren't scheduled.
+    pub fn new<A: Algorithm>(
+        date: NaiveDate,
+        entries: impl IntoIterator<Item = &'a VerseEntry>,
+        pauses: &[Pause],
+        log: &ReviewLog,
+    ) -> Self {
+        let verses = entries
+            .into_iter()
+            .filter(|entry| entry.date() <= date && !entry.is_paused(date, pauses))
+            .map(|entry| {
+                let (next, interval) = Self::next_review::<A>(entry, date, log);
+                (next, interval, entry.calculate_paused(date, pauses))
+            })
+            .collect();
+        Self { date, verses }
+    }
+
+    /// When `entry` is next due, and the interval it was scheduled with.
+    fn next_review<A: Algorithm>(
+        entry: &VerseEntry,
+        date: NaiveDate,
+        log: &ReviewLog,
+    ) -> (NaiveDate, i64) {
+        let mut algorithm = A::default();
+        let mut last: Option<NaiveDate> = None;
+        let mut interval = 0;
+        let reviews = log
+            .history(entry.reference())
+            .filter(|review| review.date() < date && review.outcome() == Outcome::Reviewed);
+        for review in reviews {
+            let elapsed = last.map_or(0, |last| (review.date() - last).num_days());
+            interval = algorithm.review(review.grade().unwrap_or(Grade::Good), elapsed);
+            last = Some(review.date());
+        }
+        match last {
+            Some(last) => (last + Days::new(interval as u64), interval),
+            None => (entry.date(), 0),
+        }
+    }
+
+    fn phase(interval: i64) -> Frequency {
+        match interval {
+            ..7 => Frequency::Daily,
+            7..28 => Frequency::Weekly,
+            28..365 => Frequency::Monthly,
+            _ => Frequency::Yearly,
+        }
+    }
+}
+
+impl<'a> Scheduler<'a> for SpacedRepetition<'a> {
+    fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    fn for_today(&self) -> VersesForADay<'a> {
+        let mut day = VersesForADay {
+            date: Some(self.date),
+            ..Default::default()
+        };
+        for (_, interval, verse) in self.verses.iter().filter(|(next, ..)| *next <= self.date) {
+            let list = match Self::phase(*interval) {
+                Frequency::Daily => &mut day.daily,
+                Frequency::Weekly => &mut day.weekly,
+                Frequency::Monthly => &mut day.monthly,
+                _ => &mut day.yearly,
+            };
+            list.push(verse.clone());
+        }
+        day
+    }
+
+    /// Verses that haven't been reviewed yet are due every day.
+    fn intervals(&self) -> Vec<(Frequency, i64, Verse<'a>)> {
+        self.verses
+            .iter()
+            .map(|(_, interval, verse)| {
+                let interval = (*interval).max(1);
+                (Self::phase(interval), interval, verse.clone())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Book, Reference};
+
+    #[test]
+    fn spaced_repetition_spaces_reviews_by_grade() {
+        let mut sm2 = Sm2::default();
+        let intervals = [
+            Grade::Good,
+            Grade::Good,
+            Grade::Good,
+            Grade::Again,
+            Grade::Good,
+        ]
+        .map(|grade| sm2.review(grade, 0));
+        assert_eq!(intervals, [1, 6, 15, 1, 1]);
+
+        // Each passing FSRS review lasts longer than the one before.
+        let mut fsrs = Fsrs::default();
+        let mut elapsed = 0;
+        for _ in 0..5 {
+            let interval = fsrs.review(Grade::Good, elapsed);
+            assert!(interval > elapsed);
+            elapsed = interval;
+        }
+
+        let start = NaiveDate::from_ymd_opt(2030, 1, 6).unwrap();
+        let reference = Reference::new(Book::John, 1, 1);
+        let entries = [VerseEntry::from_date(start, reference)];
+        let mut log = ReviewLog::default();
+        let mut reviewed = vec![];
+        for day in 0..30 {
+            let date = start + Days::new(day);
+            let today = SpacedRepetition::new::<Sm2>(date, &entries, &[], &log).for_today();
+            if today.items().next().is_some() {
+                reviewed.push(day);
+                log.mark_graded(&today, &reference, Grade::Good);
+            }
+        }
+        assert_eq!(reviewed, [0, 1, 7, 22]);
+    }
+}